@@ -0,0 +1,25 @@
+// NOTE: `PAUSABLE_UDT_CONFIG_OVERRIDES` may name a file of constants that replace some of `src/config.rs`. Only `make build-tests` sets it, with the files in `tests/configs`, to build the variants the tests crate runs against. Release builds leave it unset and compile `src/config.rs` as is.
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(config_overrides)");
+    println!("cargo:rerun-if-env-changed=PAUSABLE_UDT_CONFIG_OVERRIDES");
+    let overrides_path = match env::var("PAUSABLE_UDT_CONFIG_OVERRIDES") {
+        Ok(path) if !path.is_empty() => fs::canonicalize(&path).expect("config overrides"),
+        _ => return,
+    };
+    println!("cargo:rerun-if-changed={}", overrides_path.display());
+    println!(
+        "cargo:warning=Building with the config overrides of {}, not for deployment",
+        overrides_path.display()
+    );
+    println!("cargo:rustc-cfg=config_overrides");
+
+    let config_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/config.rs");
+    let config = format!(
+        "#[allow(dead_code)]\nmod base {{\n    include!({:?});\n}}\npub use base::*;\n\ninclude!({:?});\n",
+        config_path, overrides_path
+    );
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("config.rs");
+    fs::write(out_path, config).expect("write config");
+}
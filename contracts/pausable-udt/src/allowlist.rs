@@ -0,0 +1,214 @@
+use crate::config::{
+    ALLOWLIST_CELL_TYPE_ARGS, ALLOWLIST_CELL_TYPE_CODE_HASH, ALLOWLIST_CELL_TYPE_HASH_TYPE,
+};
+use crate::error::Error;
+use crate::registry::{self, ALLOWLIST_KIND};
+use crate::utils::{parse_config_script, script_like_to_script};
+use alloc::vec;
+use alloc::vec::Vec;
+use ckb_ssri_std::utils::high_level::{find_cell_data_by_out_point, find_out_point_by_type};
+use ckb_ssri_std::utils::should_fallback;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::{Byte32, Script};
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::high_level::{
+    load_cell_data, load_cell_lock_hash, load_cell_type, load_script, QueryIter,
+};
+use serde_molecule::{from_slice, to_vec};
+
+pub use crate::cell_data::UDTAllowlistData;
+
+impl UDTAllowlistData {
+    pub fn contains(&self, lock_hash: &[u8; 32]) -> bool {
+        self.allow_list.binary_search(lock_hash).is_ok()
+    }
+
+    /// Adds `lock_hash` to `allow_list`, which stays sorted. Returns whether it was not allowed yet.
+    pub fn allow(&mut self, lock_hash: [u8; 32]) -> bool {
+        match self.allow_list.binary_search(&lock_hash) {
+            Ok(_) => false,
+            Err(index) => {
+                self.allow_list.insert(index, lock_hash);
+                true
+            }
+        }
+    }
+
+    /// Removes `lock_hashes` from this cell, and returns whether any of them was allowed here.
+    pub fn disallow(&mut self, lock_hashes: &[[u8; 32]]) -> bool {
+        let allowed_count = self.allow_list.len();
+        self.allow_list
+            .retain(|lock_hash| !lock_hashes.contains(lock_hash));
+        self.allow_list.len() != allowed_count
+    }
+}
+
+pub fn get_allowlist_script() -> Result<Option<Script>, Error> {
+    parse_config_script(
+        ALLOWLIST_CELL_TYPE_CODE_HASH,
+        ALLOWLIST_CELL_TYPE_HASH_TYPE,
+        ALLOWLIST_CELL_TYPE_ARGS,
+    )
+}
+
+pub fn build_allowlist_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let registry_script = registry::build_registry_script(script, type_id)?;
+    let mut args: Vec<u8> = registry_script.args().unpack();
+    args.push(ALLOWLIST_KIND);
+    Ok(registry_script.as_builder().args(args.pack()).build())
+}
+
+fn load_allowlist_data_from_cell_dep(allowlist_script: &Script) -> Result<UDTAllowlistData, Error> {
+    let index = QueryIter::new(load_cell_type, Source::CellDep)
+        .position(|type_script| type_script.as_ref() == Some(allowlist_script))
+        .ok_or(Error::IncompleteAllowlist)?;
+    Ok(from_slice(&load_cell_data(index, Source::CellDep)?, false)?)
+}
+
+/// Loads every allowlist cell of the chain from `CellDep` on-chain or through SSRI otherwise. Returns `None` when the allowlist is not configured.
+pub fn load_allowlist_chain() -> Result<Option<Vec<UDTAllowlistData>>, Error> {
+    debug!("Entered load_allowlist_chain");
+    let mut next_type_script = match get_allowlist_script()? {
+        Some(script) => Some(script),
+        None => return Ok(None),
+    };
+    let mut chain: Vec<UDTAllowlistData> = Vec::new();
+    let mut seen_type_hashes: Vec<Byte32> = Vec::new();
+    while let Some(allowlist_script) = next_type_script {
+        if seen_type_hashes.contains(&allowlist_script.calc_script_hash()) {
+            return Err(Error::CyclicAllowlist);
+        }
+        seen_type_hashes.push(allowlist_script.calc_script_hash());
+
+        let allowlist_data = match should_fallback()? {
+            true => load_allowlist_data_from_cell_dep(&allowlist_script)?,
+            false => from_slice(
+                &find_cell_data_by_out_point(find_out_point_by_type(allowlist_script)?)?,
+                false,
+            )?,
+        };
+        next_type_script = allowlist_data
+            .next_type_script
+            .as_ref()
+            .map(script_like_to_script);
+        chain.push(allowlist_data);
+    }
+    Ok(Some(chain))
+}
+
+pub fn is_allowed(chain: &[UDTAllowlistData], lock_hash: &[u8; 32]) -> bool {
+    chain
+        .iter()
+        .any(|allowlist_data| allowlist_data.contains(lock_hash))
+}
+
+/// Requires the lock of every token output to be in the allowlist chain, when it is configured.
+pub fn verify_outputs_allowed() -> Result<(), Error> {
+    debug!("Entered verify_outputs_allowed");
+    let chain = match load_allowlist_chain()? {
+        Some(chain) => chain,
+        None => return Ok(()),
+    };
+    let script = load_script()?;
+    for (index, type_script) in QueryIter::new(load_cell_type, Source::Output).enumerate() {
+        if type_script.as_ref() != Some(&script) {
+            continue;
+        }
+        let lock_hash = load_cell_lock_hash(index, Source::Output)?;
+        if !is_allowed(&chain, &lock_hash) {
+            debug!("Lock hash not in allowlist: {:?}", lock_hash);
+            return Err(Error::AbortedFromAllowlist);
+        }
+    }
+    Ok(())
+}
+
+fn load_canonical_allowlist_data() -> Result<UDTAllowlistData, Error> {
+    let data = load_cell_data(0, Source::GroupOutput)?;
+    let allowlist_data: UDTAllowlistData =
+        from_slice(&data, false).map_err(|_| Error::InvalidAllowlistData)?;
+    if to_vec(&allowlist_data, false)? != data {
+        return Err(Error::InvalidAllowlistData);
+    }
+    if !allowlist_data
+        .allow_list
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+    {
+        return Err(Error::InvalidAllowlistData);
+    }
+    Ok(allowlist_data)
+}
+
+/// Tells whether going from `old` to `new` removes allowed lock hashes, adds some, or both. Chain links count as everything they lead to.
+fn allowlist_change(old: &UDTAllowlistData, new: &UDTAllowlistData) -> (bool, bool) {
+    let mut tightens = old
+        .allow_list
+        .iter()
+        .any(|lock_hash| !new.contains(lock_hash));
+    let mut loosens = new
+        .allow_list
+        .iter()
+        .any(|lock_hash| !old.contains(lock_hash));
+    if old.next_type_script.as_ref().map(script_like_to_script)
+        != new.next_type_script.as_ref().map(script_like_to_script)
+    {
+        tightens |= old.next_type_script.is_some();
+        loosens |= new.next_type_script.is_some();
+    }
+    (tightens, loosens)
+}
+
+/// Follows the chain through cell deps and makes sure it terminates without coming back to the cell being updated.
+fn verify_acyclic_chain(script: &Script, next_type_script: Script) -> Result<(), Error> {
+    debug!("Entered allowlist verify_acyclic_chain");
+    let mut seen_type_hashes: Vec<Byte32> = vec![script.calc_script_hash()];
+    let mut next_type_script = Some(next_type_script);
+    while let Some(allowlist_script) = next_type_script {
+        if seen_type_hashes.contains(&allowlist_script.calc_script_hash()) {
+            return Err(Error::CyclicAllowlist);
+        }
+        seen_type_hashes.push(allowlist_script.calc_script_hash());
+        next_type_script = load_allowlist_data_from_cell_dep(&allowlist_script)?
+            .next_type_script
+            .as_ref()
+            .map(script_like_to_script);
+    }
+    Ok(())
+}
+
+// NOTE: Removing a lock hash from the allowlist restricts it like a pause, and adding one lifts the restriction like an unpause, so both need the same authority.
+pub fn verify_allowlist_update() -> Result<(), Error> {
+    debug!("Entered verify_allowlist_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = registry::verify_type_id(&script)?;
+
+    let old_allowlist_data: UDTAllowlistData = match input_count {
+        0 => UDTAllowlistData::default(),
+        _ => from_slice(&load_cell_data(0, Source::GroupInput)?, false)?,
+    };
+    if output_count == 0 {
+        debug!("Destroying allowlist cell");
+        let (tightens, loosens) = allowlist_change(&old_allowlist_data, &Default::default());
+        return registry::verify_pause_authority(&args, tightens, loosens);
+    }
+
+    let new_allowlist_data = load_canonical_allowlist_data()?;
+    let (tightens, loosens) = allowlist_change(&old_allowlist_data, &new_allowlist_data);
+    registry::verify_pause_authority(&args, tightens, loosens)?;
+
+    if let Some(ref next_type_script_like) = new_allowlist_data.next_type_script {
+        let next_type_script = script_like_to_script(next_type_script_like);
+        let is_next_unchanged = old_allowlist_data
+            .next_type_script
+            .as_ref()
+            .map(|old_next| script_like_to_script(old_next) == next_type_script)
+            .unwrap_or(false);
+        if !is_next_unchanged {
+            verify_acyclic_chain(&script, next_type_script)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// NOTE: Data layouts of the cells this script validates. This file must not depend on syscalls, since the tests crate includes it as is to build cell data.
+use alloc::vec::Vec;
+use ckb_ssri_std::public_module_traits::udt::ScriptLike;
+use serde::{Deserialize, Serialize};
+use serde_molecule::dynvec_serde;
+
+/// Superset of `UDTPausableData`. Readers that only know `UDTPausableData` ignore the trailing fields, and lock hashes in `pause_list` keep meaning full freeze.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PausableRegistryData {
+    pub pause_list: Vec<[u8; 32]>,
+    pub next_type_script: Option<ScriptLike>,
+    pub restriction_list: Vec<PauseRestriction>,
+    pub global_pause: Option<GlobalPause>,
+    pub expiry_list: Vec<PauseExpiry>,
+    pub frozen_cells: Vec<FrozenCell>,
+    pub lock_code_hash_rules: Vec<LockCodeHashRule>,
+    pub blocked_type_hashes: Vec<[u8; 32]>,
+    pub guardian_lock_hash: Option<[u8; 32]>,
+    pub pending_unpauses: Vec<PendingUnpause>,
+    pub approvers: Vec<[u8; 32]>,
+    pub approval_threshold: u8,
+    pub redemption_lock_hashes: Vec<[u8; 32]>,
+}
+
+/// A partial restriction: `level` is either `RESTRICTION_BLOCK_SEND` or `RESTRICTION_BLOCK_RECEIVE`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PauseRestriction {
+    pub lock_hash: [u8; 32],
+    pub level: u8,
+}
+
+/// Expiry of the entry of `lock_hash` in the same cell, whether it is in `pause_list` or `restriction_list`. The entry stops applying from epoch number `expiry_epoch` on.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PauseExpiry {
+    pub lock_hash: [u8; 32],
+    pub expiry_epoch: u64,
+}
+
+/// Marks the entry of `lock_hash` in the same cell as pending unpause since epoch number `scheduled_epoch`. A `scheduled_epoch` of 0 stands for the epoch of the block that created the cell, which is when `UDTPausable.schedule_unpause` wrote it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PendingUnpause {
+    pub lock_hash: [u8; 32],
+    pub scheduled_epoch: u64,
+}
+
+/// Fully freezes every lock with this `code_hash` and `hash_type`, whatever its args are.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LockCodeHashRule {
+    pub code_hash: [u8; 32],
+    pub hash_type: u8,
+}
+
+/// A single token cell that can not be spent, identified by its out point and regardless of its lock.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FrozenCell {
+    pub tx_hash: [u8; 32],
+    pub index: u32,
+}
+
+/// Halts every transaction of the token that is not in owner mode from the moment it is set until `end_epoch` (exclusive), or until it is lifted when `end_epoch` is 0.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct GlobalPause {
+    pub end_epoch: u64,
+}
+
+// NOTE: The supply cell is typed by this same binary with args <owner lock hash> <type id> 0x02. Only its creation and destruction need owner mode, since holders update it whenever they burn.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UDTSupplyData {
+    pub udt_type_hash: [u8; 32],
+    pub total_supply: u128,
+    pub max_supply: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RoleMembers {
+    pub role: u8,
+    pub lock_hashes: Vec<[u8; 32]>,
+}
+
+// NOTE: The roles cell is typed by this same binary with args <owner lock hash> <type id> 0x03. Creating it requires owner mode, and any later change requires an ADMIN lock among the inputs.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UDTRolesData {
+    #[serde(with = "dynvec_serde")]
+    pub roles: Vec<RoleMembers>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MinterQuota {
+    pub lock_hash: [u8; 32],
+    pub remaining_quota: u128,
+}
+
+// NOTE: The quota cell is typed by this same binary with args <owner lock hash> <type id> 0x05. Owner mode may change it freely, while a minter may only spend its own quota by exactly the minted amount.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UDTQuotaData {
+    pub udt_type_hash: [u8; 32],
+    pub quotas: Vec<MinterQuota>,
+}
+
+// NOTE: Allowlist cells are typed by this same binary with args <owner lock hash> <type id> 0x04. They are chained through `next_type_script` like pause list cells, starting from the one configured in `config.rs`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UDTAllowlistData {
+    pub allow_list: Vec<[u8; 32]>,
+    pub next_type_script: Option<ScriptLike>,
+}
+
+// NOTE: Proposal cells are typed by this same binary with args <owner lock hash> <type id> 0x06. Each one carries a change to the pause list of one pause list cell, collects approvals over as many transactions as needed, and is consumed by the transaction that applies the change.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UDTPauseProposalData {
+    pub registry_type_hash: [u8; 32],
+    pub pause_list_additions: Vec<[u8; 32]>,
+    pub pause_list_removals: Vec<[u8; 32]>,
+    pub approvals: Vec<[u8; 32]>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UDTPausableSmtData {
+    pub root: [u8; 32],
+}
+
+/// Siblings from the leaf up to the root. Bit `height` of the bitmap tells whether the sibling at that height is non-zero and therefore present in `siblings`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SmtProof {
+    pub bitmap: [u8; 32],
+    pub siblings: Vec<[u8; 32]>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SmtLockProof {
+    pub lock_hash: [u8; 32],
+    pub proof: SmtProof,
+}
+
+/// Non-membership proofs for every lock hash involved in a token transaction, carried in the token witness.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SmtLockProofs {
+    #[serde(with = "dynvec_serde")]
+    pub proofs: Vec<SmtLockProof>,
+}
+
+/// A single pause (paused = 1) or unpause (paused = 0), proven against the root left by the previous update.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SmtUpdate {
+    pub lock_hash: [u8; 32],
+    pub paused: u8,
+    pub proof: SmtProof,
+}
+
+/// Updates applied to the SMT registry cell, carried in its witness so that indexers can rebuild the leaves.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SmtUpdates {
+    #[serde(with = "dynvec_serde")]
+    pub updates: Vec<SmtUpdate>,
+}
@@ -9,6 +9,81 @@ pub const INITIAL_EXTERNAL_DATA_CELL_TYPE_CODE_HASH: &str = "";
 pub const INITIAL_EXTERNAL_DATA_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
 pub const INITIAL_EXTERNAL_DATA_CELL_TYPE_ARGS: &str = "";
 
+// Leave empty to disable the SMT pause registry
+pub const SMT_REGISTRY_CELL_TYPE_CODE_HASH: &str = "";
+pub const SMT_REGISTRY_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const SMT_REGISTRY_CELL_TYPE_ARGS: &str = "";
+
+// Leave empty to disable supply tracking
+pub const SUPPLY_CELL_TYPE_CODE_HASH: &str = "";
+pub const SUPPLY_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const SUPPLY_CELL_TYPE_ARGS: &str = "";
+
+// Leave empty to let authorized minters and the owner mint without quota
+pub const QUOTA_CELL_TYPE_CODE_HASH: &str = "";
+pub const QUOTA_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const QUOTA_CELL_TYPE_ARGS: &str = "";
+
+// Leave empty to keep the owner lock as the only authority
+pub const ROLES_CELL_TYPE_CODE_HASH: &str = "";
+pub const ROLES_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const ROLES_CELL_TYPE_ARGS: &str = "";
+
+// Leave empty to let any lock hold the token, otherwise only locks in the allowlist chain may
+pub const ALLOWLIST_CELL_TYPE_CODE_HASH: &str = "";
+pub const ALLOWLIST_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const ALLOWLIST_CELL_TYPE_ARGS: &str = "";
+
+// Leave empty to disable clawback, otherwise owner mode may move paused funds to this lock
+pub const RECOVERY_LOCK_CODE_HASH: &str = "";
+pub const RECOVERY_LOCK_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const RECOVERY_LOCK_ARGS: &str = "";
+
+// Largest net outflow of a transaction out of owner mode (what its input locks lose, summed), 0 for no limit
+pub const MAX_TRANSFER_AMOUNT: u128 = 0;
+
+// Lower limit for transactions spending from any of these lock hashes, 0 for no limit
+pub const WATCH_LIST_LOCK_HASHES: &[&str] = &[];
+pub const WATCH_LIST_MAX_TRANSFER_AMOUNT: u128 = 0;
+
+// Leave empty to disable transfer fees, otherwise transfers pay FEE_RATE_BPS of the transferred amount to this lock
+pub const TREASURY_LOCK_CODE_HASH: &str = "";
+pub const TREASURY_LOCK_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+pub const TREASURY_LOCK_ARGS: &str = "";
+pub const FEE_RATE_BPS: u16 = 0;
+
+// Epochs an unpause must stay scheduled before it can be finalized, 0 to lift pauses at once (42 epochs are about 7 days)
+pub const UNPAUSE_DELAY_EPOCHS: u64 = 0;
+
+// Leave empty to disable compliance screening, otherwise transfers out of owner mode need a signature of this oracle (33-byte compressed secp256k1 public key)
+pub const COMPLIANCE_ORACLE_PUBKEY: &str = "";
+
+// What the compliance oracle signs: the transaction hash, or the lock hashes involved in the transfer along with the first input as a nonce
+#[allow(dead_code)]
+pub enum OracleAttestationScope {
+    TransactionHash,
+    LockHashes,
+}
+pub const ORACLE_ATTESTATION_SCOPE: OracleAttestationScope = OracleAttestationScope::TransactionHash;
+
+// Who may burn tokens: any holder, only owner mode, or nobody, owner mode included
+#[allow(dead_code)]
+pub enum BurnPolicy {
+    Holders,
+    OwnerOnly,
+    Disabled,
+}
+pub const BURN_POLICY: BurnPolicy = BurnPolicy::Holders;
+
+// Format required for data after the 16-byte amount in token outputs
+#[allow(dead_code)]
+pub enum ExtensionDataFormat {
+    Any,
+    Forbidden,
+    MoleculeTable,
+}
+pub const EXTENSION_DATA_FORMAT: ExtensionDataFormat = ExtensionDataFormat::Any;
+
 pub const NAME: &str = "Pausable UDT";
 pub const SYMBOL: &str = "PUDT";
 pub const DECIMALS: u8 = 6;
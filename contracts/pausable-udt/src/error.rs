@@ -80,6 +80,76 @@ pub enum Error {
     IncompletePauseList,
     CyclicPauseList,
     InvalidPauseData,
+
+    // * UDT Pausable Registry Error
+    InvalidRegistryArgs,
+    InvalidTypeId,
+    NonCanonicalPauseList,
+    InvalidSmtProof,
+    AbortedFromGlobalPause,
+
+    // * UDT Supply Error
+    SupplyNotTracked,
+    InvalidSupplyData,
+    ExceededMaxSupply,
+
+    // * UDT Roles Error
+    MissingRolesCell,
+    InvalidRolesData,
+    NoRolePermission,
+
+    // * UDT Data Error
+    InvalidExtensionData,
+
+    // * UDT Frozen Cell Error
+    AbortedFromFrozenCell,
+
+    // * UDT Allowlist Error
+    AbortedFromAllowlist,
+    IncompleteAllowlist,
+    CyclicAllowlist,
+    InvalidAllowlistData,
+
+    // * UDT Clawback Error
+    NoClawbackPermission,
+
+    // * UDT Transfer Limit Error
+    ExceededTransferLimit,
+
+    // * UDT Transfer Fee Error
+    InsufficientTransferFee,
+
+    // * UDT Mint Quota Error
+    QuotaNotTracked,
+    InvalidQuotaData,
+    ExceededMintQuota,
+
+    // * UDT Blocked Type Error
+    AbortedFromBlockedType,
+
+    // * UDT Unpause Delay Error
+    UnpauseNotScheduled,
+    UnpauseNotDue,
+
+    // * UDT Approval Error
+    NotEnoughApprovals,
+    InvalidApprovers,
+
+    // * UDT Proposal Error
+    InvalidProposalData,
+    InvalidApproval,
+    ProposalNotApproved,
+    ProposalMismatch,
+
+    // * UDT Compliance Oracle Error
+    MissingOracleAttestation,
+    InvalidOracleAttestation,
+
+    // * UDT Redemption Error
+    NotRedemptionLock,
+
+    // * UDT Token Args Error
+    InvalidTokenArgs,
 }
 
 #[allow(non_snake_case, unused)]
@@ -1,41 +1,204 @@
 use crate::{
+    allowlist,
     error::Error,
     modules::PausableUDT,
+    registry::{
+        self, FrozenCell, PausableRegistryData, RESTRICTION_BLOCK_RECEIVE, RESTRICTION_BLOCK_SEND,
+    },
+    oracle, proposal, quota, roles, smt, supply,
+    utils::{
+        check_owner_mode, collect_inputs_amount, collect_outputs_amount, current_epoch_lower_bound,
+        get_recovery_lock,
+    },
 };
 
-use alloc::vec;
 use alloc::vec::Vec;
 use ckb_std::{
-    ckb_constants::Source, debug, high_level::load_cell_lock_hash
+    ckb_constants::Source, ckb_types::{bytes::Bytes, packed::Script, prelude::*}, debug,
+    high_level::{
+        load_cell_lock, load_cell_lock_hash, load_cell_type, load_cell_type_hash, load_input,
+        load_script, QueryIter,
+    },
 };
 
-use ckb_ssri_std::public_module_traits::udt::{UDTPausable, UDT};
+use ckb_ssri_std::public_module_traits::udt::UDT;
 
 pub fn fallback() -> Result<(), Error> {
     debug!("Entered fallback");
-    let mut lock_hashes: Vec<[u8; 32]> = vec![];
+    let args: Bytes = load_script()?.args().unpack();
+    if registry::is_registry_script(&args) {
+        return registry::verify_registry_update();
+    }
+    if registry::is_smt_registry_script(&args) {
+        return smt::verify_smt_registry_update();
+    }
+    if registry::is_supply_script(&args) {
+        return supply::verify_supply_update();
+    }
+    if registry::is_roles_script(&args) {
+        return roles::verify_roles_update();
+    }
+    if registry::is_allowlist_script(&args) {
+        return allowlist::verify_allowlist_update();
+    }
+    if registry::is_quota_script(&args) {
+        return quota::verify_quota_update();
+    }
+    if registry::is_proposal_script(&args) {
+        return proposal::verify_proposal_update();
+    }
+
+    let registry_chain = PausableUDT::load_registry_chain()?;
+    let current_epoch = current_epoch_lower_bound()?;
+    if let Some(global_pause) = registry::global_pause(&registry_chain) {
+        if global_pause.is_active(current_epoch) && !check_owner_mode(&args)? {
+            debug!("Aborted from global pause: {:?}", global_pause);
+            return Err(Error::AbortedFromGlobalPause);
+        }
+    }
 
-    let mut index = 0;
-    while let Ok(lock_hash) = load_cell_lock_hash(index, Source::Input) {
-        lock_hashes.push(lock_hash);
-        index += 1;
+    // A clawback may spend paused inputs and frozen cells, while outputs are still checked
+    let is_clawback = is_clawback(&args)?;
+    // A redemption may spend paused inputs too, but not frozen cells
+    let is_redemption = is_redemption(&registry_chain)?;
+    let may_spend_paused = is_clawback || is_redemption;
+    let input_cells: Vec<FrozenCell> = QueryIter::new(load_input, Source::Input)
+        .map(|input| FrozenCell::from(&input.previous_output()))
+        .collect();
+    if !is_clawback
+        && registry::frozen_cells(&registry_chain, &input_cells)
+            .into_iter()
+            .any(|frozen| frozen)
+    {
+        return Err(Error::AbortedFromFrozenCell);
     }
 
-    index = 0;
-    while let Ok(lock_hash) = load_cell_lock_hash(index, Source::Output) {
-        lock_hashes.push(lock_hash);
-        index += 1;
+    // Transactions touching a blocked contract (e.g. an exploited DEX or bridge) are rejected
+    let type_hashes: Vec<[u8; 32]> = QueryIter::new(load_cell_type_hash, Source::Input)
+        .chain(QueryIter::new(load_cell_type_hash, Source::Output))
+        .flatten()
+        .collect();
+    if registry::blocked_type_hashes(&registry_chain, &type_hashes)
+        .into_iter()
+        .any(|blocked| blocked)
+    {
+        return Err(Error::AbortedFromBlockedType);
+    }
+
+    let input_locks: Vec<Script> = QueryIter::new(load_cell_lock, Source::Input).collect();
+    let output_locks: Vec<Script> = QueryIter::new(load_cell_lock, Source::Output).collect();
+    let input_lock_hashes: Vec<[u8; 32]> = input_locks
+        .iter()
+        .map(|lock| lock.calc_script_hash().unpack())
+        .collect();
+    let output_lock_hashes: Vec<[u8; 32]> = output_locks
+        .iter()
+        .map(|lock| lock.calc_script_hash().unpack())
+        .collect();
+
+    // Whole lock families can be paused by code hash
+    if (!may_spend_paused
+        && registry::lock_code_hash_paused(&registry_chain, &input_locks)
+            .into_iter()
+            .any(|paused| paused))
+        || registry::lock_code_hash_paused(&registry_chain, &output_locks)
+            .into_iter()
+            .any(|paused| paused)
+    {
+        return Err(Error::AbortedFromPause);
     }
 
-    if PausableUDT::is_paused(&lock_hashes)?.iter().any(|&b| b) {
+    // Inputs are checked against send restrictions and outputs against receive restrictions
+    let mut lock_hashes = input_lock_hashes.clone();
+    lock_hashes.extend(output_lock_hashes.iter());
+    let levels = registry::restriction_levels(&registry_chain, &lock_hashes, current_epoch);
+    let (input_levels, output_levels) = levels.split_at(input_lock_hashes.len());
+    if (!may_spend_paused && input_levels.iter().any(|&level| level & RESTRICTION_BLOCK_SEND != 0))
+        || output_levels
+            .iter()
+            .any(|&level| level & RESTRICTION_BLOCK_RECEIVE != 0)
+    {
         return Err(Error::AbortedFromPause);
     }
+    let mut smt_lock_hashes = match may_spend_paused {
+        true => output_lock_hashes,
+        false => lock_hashes.clone(),
+    };
+    smt_lock_hashes.sort();
+    smt_lock_hashes.dedup();
+    smt::verify_not_paused_by_smt(&smt_lock_hashes)?;
+    lock_hashes.sort();
+    lock_hashes.dedup();
+    allowlist::verify_outputs_allowed()?;
+    // Transfers out of owner mode must be screened by the compliance oracle when one is configured, redemptions included: paying out a paused holder is still up to the oracle
+    if !check_owner_mode(&args)? {
+        oracle::verify_oracle_attestation(&lock_hashes)?;
+    }
+
+    let inputs_amount = collect_inputs_amount()?;
+    let outputs_amount = collect_outputs_amount()?;
+    supply::verify_supply_tracked(inputs_amount, outputs_amount)?;
+    quota::verify_quota_tracked(inputs_amount, outputs_amount)?;
+    if !check_owner_mode(&args)? {
+        PausableUDT::verify_transfer_limit()?;
+    }
 
     match PausableUDT::verify_mint() {
         Ok(_) => Ok(()),
         Err(_) => match PausableUDT::verify_transfer() {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                // Redemptions go back to the issuer and pay no transfer fee
+                if !is_redemption {
+                    PausableUDT::verify_transfer_fee()?;
+                }
+                PausableUDT::verify_burn()
+            }
             Err(e) => Err(e),
         },
     }
 }
+
+/// A clawback is a transaction in owner mode with token outputs, all of which use the configured recovery lock.
+fn is_clawback(args: &Bytes) -> Result<bool, Error> {
+    let recovery_lock_hash: [u8; 32] = match get_recovery_lock()? {
+        Some(recovery_lock) => recovery_lock.calc_script_hash().unpack(),
+        None => return Ok(false),
+    };
+    if !check_owner_mode(args)? {
+        return Ok(false);
+    }
+    let script = load_script()?;
+    let mut has_recovery_output = false;
+    for (index, type_script) in QueryIter::new(load_cell_type, Source::Output).enumerate() {
+        if type_script.as_ref() != Some(&script) {
+            continue;
+        }
+        if load_cell_lock_hash(index, Source::Output)? != recovery_lock_hash {
+            return Ok(false);
+        }
+        has_recovery_output = true;
+    }
+    debug!("is_clawback: {}", has_recovery_output);
+    Ok(has_recovery_output)
+}
+
+/// A redemption sends the whole token amount of its inputs to redemption locks of the pause list chain, with no other token outputs. It pays no transfer fee.
+fn is_redemption(registry_chain: &[PausableRegistryData]) -> Result<bool, Error> {
+    let script = load_script()?;
+    let mut output_lock_hashes: Vec<[u8; 32]> = Vec::new();
+    for (index, type_script) in QueryIter::new(load_cell_type, Source::Output).enumerate() {
+        if type_script.as_ref() == Some(&script) {
+            output_lock_hashes.push(load_cell_lock_hash(index, Source::Output)?);
+        }
+    }
+    if output_lock_hashes.is_empty()
+        || !registry::redemption_lock_hashes(registry_chain, &output_lock_hashes)
+            .into_iter()
+            .all(|redemption| redemption)
+    {
+        return Ok(false);
+    }
+    let is_redemption = collect_inputs_amount()? == collect_outputs_amount()?;
+    debug!("is_redemption: {}", is_redemption);
+    Ok(is_redemption)
+}
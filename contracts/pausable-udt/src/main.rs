@@ -7,10 +7,11 @@ extern crate alloc;
 use alloc::borrow::Cow;
 use alloc::ffi::CString;
 use ckb_ssri_std::prelude::decode_u8_32_vector;
+use core::ffi::CStr;
 
 use ckb_ssri_std::utils::should_fallback;
 use ckb_ssri_std_proc_macro::ssri_methods;
-use ckb_std::ckb_types::packed::{Byte32, Bytes, Script, ScriptBuilder, Transaction};
+use ckb_std::ckb_types::packed::{Byte32, Bytes, OutPoint, OutPointVec, Script, ScriptBuilder, Transaction};
 use ckb_std::debug;
 #[cfg(not(test))]
 use ckb_std::default_alloc;
@@ -29,11 +30,27 @@ use ckb_std::syscalls::{pipe, write};
 use config::*;
 use error::Error;
 
+mod allowlist;
+mod cell_data;
+#[cfg(not(config_overrides))]
 mod config;
+// Test builds only, see build.rs
+#[cfg(config_overrides)]
+mod config {
+    include!(concat!(env!("OUT_DIR"), "/config.rs"));
+}
 mod error;
 mod fallback;
 mod modules;
 mod molecule;
+mod oracle;
+mod proposal;
+mod quota;
+mod registry;
+mod roles;
+mod smt;
+mod smt_tree;
+mod supply;
 mod utils;
 use ::molecule::prelude::Reader;
 
@@ -67,6 +84,17 @@ pub fn get_pausable_data() -> Result<UDTPausableData, Error> {
     })
 }
 
+/// Parses the optional transaction argument of SSRI methods, where an empty argument means none.
+fn parse_optional_tx(arg: &CStr) -> Result<Option<Transaction>, Error> {
+    if arg.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        Transaction::from_compatible_slice(&decode_hex(arg)?)
+            .map_err(|_| Error::MoleculeVerificationError)?,
+    ))
+}
+
 fn program_entry_wrap() -> Result<(), Error> {
     let argv = ckb_std::env::argv();
 
@@ -96,6 +124,70 @@ fn program_entry_wrap() -> Result<(), Error> {
             let response = modules::PausableUDT::enumerate_paused(offset, limit)?;
             Ok(Cow::from(response.as_bytes().to_vec()))
         },
+        "UDTPausable.restriction_levels" => {
+            let current_epoch: Option<u64> = match argv.get(2) {
+                Some(arg) if !arg.is_empty() => Some(u64::from_le_bytes(decode_hex(arg.as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?)),
+                _ => None,
+            };
+            let response = modules::PausableUDT::restriction_levels(&decode_u8_32_vector(decode_hex(argv[1].as_ref())?).map_err(|_|error::Error::SSRIMethodsArgsInvalid)?, current_epoch)?;
+            Ok(Cow::from(response.pack().as_bytes().to_vec()))
+        },
+        "UDTPausable.enumerate_pause_entries" => {
+            let offset = u64::from_le_bytes(decode_hex(argv[1].as_ref())?.try_into().unwrap_or_default());
+            let limit = u64::from_le_bytes(decode_hex(argv[2].as_ref())?.try_into().unwrap_or_default());
+            let response = modules::PausableUDT::enumerate_pause_entries(offset, limit)?;
+            Ok(Cow::from(serde_molecule::to_vec(&response, false)?))
+        },
+        "UDTPausable.is_globally_paused" => {
+            let current_epoch: Option<u64> = match argv.get(1) {
+                Some(arg) if !arg.is_empty() => Some(u64::from_le_bytes(decode_hex(arg.as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?)),
+                _ => None,
+            };
+            let response = modules::PausableUDT::is_globally_paused(current_epoch)?;
+            Ok(Cow::from(vec![response as u8]))
+        },
+        "UDTPausable.is_lock_paused" => {
+            let locks = molecule::ScriptVec::from_slice(decode_hex(argv[1].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?
+                .into_iter()
+                .map(|script| Script::from_slice(script.as_slice()).map_err(|_|Error::MoleculeVerificationError))
+                .collect::<Result<Vec<Script>, Error>>()?;
+            let response = modules::PausableUDT::is_lock_paused(&locks)?;
+            let response_bytes = response.iter().map(|b| if *b { 1 } else { 0 }).collect::<Vec<u8>>().pack();
+            Ok(Cow::from(response_bytes.as_bytes().to_vec()))
+        },
+        "UDTPausable.enumerate_lock_code_hash_rules" => {
+            let offset = u64::from_le_bytes(decode_hex(argv[1].as_ref())?.try_into().unwrap_or_default());
+            let limit = u64::from_le_bytes(decode_hex(argv[2].as_ref())?.try_into().unwrap_or_default());
+            let response = modules::PausableUDT::enumerate_lock_code_hash_rules(offset, limit)?;
+            Ok(Cow::from(serde_molecule::to_vec(&response, false)?))
+        },
+        "UDTPausable.is_type_blocked" => {
+            let response = modules::PausableUDT::is_type_blocked(&decode_u8_32_vector(decode_hex(argv[1].as_ref())?).map_err(|_|error::Error::SSRIMethodsArgsInvalid)?)?;
+            let response_bytes = response.iter().map(|b| if *b { 1 } else { 0 }).collect::<Vec<u8>>().pack();
+            Ok(Cow::from(response_bytes.as_bytes().to_vec()))
+        },
+        "UDTPausable.is_redemption_lock" => {
+            let response = modules::PausableUDT::is_redemption_lock(&decode_u8_32_vector(decode_hex(argv[1].as_ref())?).map_err(|_|error::Error::SSRIMethodsArgsInvalid)?)?;
+            let response_bytes = response.iter().map(|b| if *b { 1 } else { 0 }).collect::<Vec<u8>>().pack();
+            Ok(Cow::from(response_bytes.as_bytes().to_vec()))
+        },
+        "UDTPausable.is_cell_frozen" => {
+            let out_point_vec: Vec<OutPoint> = OutPointVec::from_slice(decode_hex(argv[1].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?.into_iter().collect();
+            let response = modules::PausableUDT::is_cell_frozen(&out_point_vec)?;
+            let response_bytes = response.iter().map(|b| if *b { 1 } else { 0 }).collect::<Vec<u8>>().pack();
+            Ok(Cow::from(response_bytes.as_bytes().to_vec()))
+        },
+        "UDTPausable.is_allowed" => {
+            let response = modules::PausableUDT::is_allowed(&decode_u8_32_vector(decode_hex(argv[1].as_ref())?).map_err(|_|error::Error::SSRIMethodsArgsInvalid)?)?;
+            let response_bytes = response.iter().map(|b| if *b { 1 } else { 0 }).collect::<Vec<u8>>().pack();
+            Ok(Cow::from(response_bytes.as_bytes().to_vec()))
+        },
+        "UDTPausable.enumerate_allowed" => {
+            let offset = u64::from_le_bytes(decode_hex(argv[1].as_ref())?.try_into().unwrap_or_default());
+            let limit = u64::from_le_bytes(decode_hex(argv[2].as_ref())?.try_into().unwrap_or_default());
+            let response = modules::PausableUDT::enumerate_allowed(offset, limit)?;
+            Ok(Cow::from(response.as_bytes().to_vec()))
+        },
         "UDT.transfer" => {
             debug!("program_entry_wrap | Entered UDT.transfer");
             let to_lock_vec_molecule = molecule::ScriptVec::from_slice(decode_hex(argv[2].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?;
@@ -121,13 +213,7 @@ fn program_entry_wrap() -> Result<(), Error> {
                 Err(Error::SSRIMethodsArgsInvalid)?;
             }
 
-            let tx: Option<Transaction>;
-            if argv[1].as_ref().to_str()? == "" {
-                tx = None;
-            } else {
-                let parsed_tx: Transaction = Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
-                tx = Some(parsed_tx);
-            }
+            let tx = parse_optional_tx(&argv[1])?;
 
             Ok(Cow::from(modules::PausableUDT::transfer(tx, to_lock_vec, to_amount_vec)?.as_bytes().to_vec()))
         },
@@ -158,16 +244,65 @@ fn program_entry_wrap() -> Result<(), Error> {
                 Err(Error::SSRIMethodsArgsInvalid)?;
             }
 
-            let tx: Option<Transaction>;
-            if argv[1].as_ref().to_str()? == ""{
-                tx = None;
-            } else {
-                let parsed_tx: Transaction = Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
-                tx = Some(parsed_tx);
-            }
+            let tx = parse_optional_tx(&argv[1])?;
 
             Ok(Cow::from(modules::PausableUDT::mint(tx, to_lock_vec, to_amount_vec)?.as_bytes().to_vec()))
         },
+        "UDT.burn" => {
+            debug!("program_entry_wrap | Entered UDT.burn");
+            let burn_out_point_vec: Vec<OutPoint> = OutPointVec::from_slice(decode_hex(argv[2].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?.into_iter().collect();
+            debug!("program_entry_wrap | burn_out_point_vec: {:?}", burn_out_point_vec);
+
+            let burn_amount = u128::from_le_bytes(decode_hex(argv[3].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+            debug!("program_entry_wrap | burn_amount: {}", burn_amount);
+
+            if argv[2].is_empty() || argv[3].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::burn(tx, burn_out_point_vec, burn_amount)?.as_bytes().to_vec()))
+        },
+        "UDT.total_supply" => Ok(Cow::from(modules::PausableUDT::total_supply()?.to_le_bytes().to_vec())),
+        "UDT.remaining_quota" => {
+            let lock_hash: [u8; 32] = decode_hex(argv[1].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?;
+            Ok(Cow::from(modules::PausableUDT::remaining_quota(&lock_hash)?.to_le_bytes().to_vec()))
+        },
+        "UDT.set_mint_quotas" => {
+            debug!("program_entry_wrap | Entered UDT.set_mint_quotas");
+            let quota_data: quota::UDTQuotaData = serde_molecule::from_slice(&decode_hex(argv[2].as_ref())?, false)?;
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::set_mint_quotas(tx, quota_data.quotas)?.as_bytes().to_vec()))
+        },
+        "UDT.roles" => Ok(Cow::from(serde_molecule::to_vec(&modules::PausableUDT::roles()?, false)?)),
+        "UDT.has_role" => {
+            let role = u8::from_le_bytes(decode_hex(argv[1].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+            let lock_hash: [u8; 32] = decode_hex(argv[2].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?;
+            let response = modules::PausableUDT::has_role(role, &lock_hash)?;
+            Ok(Cow::from(vec![response as u8]))
+        },
+        "UDT.update_roles" => {
+            debug!("program_entry_wrap | Entered UDT.update_roles");
+            let roles_data: roles::UDTRolesData = serde_molecule::from_slice(&decode_hex(argv[2].as_ref())?, false)?;
+            debug!("program_entry_wrap | roles_data: {:?}", roles_data);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::update_roles(tx, roles_data)?.as_bytes().to_vec()))
+        },
+        "UDT.create_supply_cell" => {
+            debug!("program_entry_wrap | Entered UDT.create_supply_cell");
+            let supply_lock = Script::from_compatible_slice(&decode_hex(argv[2].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
+            let total_supply = u128::from_le_bytes(decode_hex(argv[3].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+            let max_supply = u128::from_le_bytes(decode_hex(argv[4].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::create_supply_cell(tx, supply_lock, total_supply, max_supply)?.as_bytes().to_vec()))
+        },
         "UDTPausable.pause" => {
             debug!("program_entry_wrap | Entered UDTPausable.pause");
             let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
@@ -177,13 +312,7 @@ fn program_entry_wrap() -> Result<(), Error> {
                 Err(Error::SSRIMethodsArgsInvalid)?;
             }
 
-            let tx: Option<Transaction>;
-            if argv[1].as_ref().to_str()? == ""{
-                tx = None;
-            } else {
-                let parsed_tx: Transaction = Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
-                tx = Some(parsed_tx);
-            }
+            let tx = parse_optional_tx(&argv[1])?;
 
             Ok(Cow::from(modules::PausableUDT::pause(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
         },
@@ -196,14 +325,310 @@ fn program_entry_wrap() -> Result<(), Error> {
                 Err(Error::SSRIMethodsArgsInvalid)?;
             }
 
-            let tx: Option<Transaction>;
-            if argv[1].as_ref().to_str()? == ""{
-                tx = None;
+            let tx = parse_optional_tx(&argv[1])?;
+            Ok(Cow::from(modules::PausableUDT::unpause(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.pause_with_level" => {
+            debug!("program_entry_wrap | Entered UDTPausable.pause_with_level");
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let level: u8 = u8::from_le_bytes(decode_hex(argv[3].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+            let expiry_epoch: Option<u64> = match argv.get(4) {
+                Some(arg) if !arg.is_empty() => Some(u64::from_le_bytes(decode_hex(arg.as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?)),
+                _ => None,
+            };
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::pause_with_level(tx, &lock_hashes_vec, level, expiry_epoch)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.set_global_pause" => {
+            debug!("program_entry_wrap | Entered UDTPausable.set_global_pause");
+            let global_pause: Option<registry::GlobalPause> = if argv[2].is_empty() {
+                None
             } else {
-                let parsed_tx: Transaction = Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
-                tx = Some(parsed_tx);
+                Some(serde_molecule::from_slice(&decode_hex(argv[2].as_ref())?, false)?)
+            };
+            debug!("program_entry_wrap | global_pause: {:?}", global_pause);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::set_global_pause(tx, global_pause)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.compact_expired_pauses" => {
+            debug!("program_entry_wrap | Entered UDTPausable.compact_expired_pauses");
+            let current_epoch = u64::from_le_bytes(decode_hex(argv[2].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::compact_expired_pauses(tx, current_epoch)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.freeze_cells" => {
+            debug!("program_entry_wrap | Entered UDTPausable.freeze_cells");
+            let out_point_vec: Vec<OutPoint> = OutPointVec::from_slice(decode_hex(argv[2].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?.into_iter().collect();
+            debug!("program_entry_wrap | out_point_vec: {:?}", out_point_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
             }
-            Ok(Cow::from(modules::PausableUDT::unpause(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::freeze_cells(tx, &out_point_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.unfreeze_cells" => {
+            debug!("program_entry_wrap | Entered UDTPausable.unfreeze_cells");
+            let out_point_vec: Vec<OutPoint> = OutPointVec::from_slice(decode_hex(argv[2].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?.into_iter().collect();
+            debug!("program_entry_wrap | out_point_vec: {:?}", out_point_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::unfreeze_cells(tx, &out_point_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.add_to_allowlist" => {
+            debug!("program_entry_wrap | Entered UDTPausable.add_to_allowlist");
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::add_to_allowlist(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.remove_from_allowlist" => {
+            debug!("program_entry_wrap | Entered UDTPausable.remove_from_allowlist");
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::remove_from_allowlist(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.clawback" => {
+            debug!("program_entry_wrap | Entered UDTPausable.clawback");
+            let clawback_out_point_vec: Vec<OutPoint> = OutPointVec::from_slice(decode_hex(argv[2].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?.into_iter().collect();
+            debug!("program_entry_wrap | clawback_out_point_vec: {:?}", clawback_out_point_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::clawback(tx, clawback_out_point_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.pause_lock_code_hashes" => {
+            debug!("program_entry_wrap | Entered UDTPausable.pause_lock_code_hashes");
+            let rules: registry::LockCodeHashRules = serde_molecule::from_slice(&decode_hex(argv[2].as_ref())?, false)?;
+            debug!("program_entry_wrap | rules: {:?}", rules);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::pause_lock_code_hashes(tx, &rules.rules)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.unpause_lock_code_hashes" => {
+            debug!("program_entry_wrap | Entered UDTPausable.unpause_lock_code_hashes");
+            let rules: registry::LockCodeHashRules = serde_molecule::from_slice(&decode_hex(argv[2].as_ref())?, false)?;
+            debug!("program_entry_wrap | rules: {:?}", rules);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::unpause_lock_code_hashes(tx, &rules.rules)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.block_type_hashes" => {
+            debug!("program_entry_wrap | Entered UDTPausable.block_type_hashes");
+            let type_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | type_hashes_vec: {:?}", type_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::block_type_hashes(tx, &type_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.unblock_type_hashes" => {
+            debug!("program_entry_wrap | Entered UDTPausable.unblock_type_hashes");
+            let type_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | type_hashes_vec: {:?}", type_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::unblock_type_hashes(tx, &type_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.set_guardian" => {
+            debug!("program_entry_wrap | Entered UDTPausable.set_guardian");
+            let guardian_lock_hash: Option<[u8; 32]> = match decode_hex(argv[2].as_ref())? {
+                lock_hash if lock_hash.is_empty() => None,
+                lock_hash => Some(lock_hash.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?),
+            };
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::set_guardian(tx, guardian_lock_hash)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.schedule_unpause" => {
+            debug!("program_entry_wrap | Entered UDTPausable.schedule_unpause");
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::schedule_unpause(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.finalize_unpause" => {
+            debug!("program_entry_wrap | Entered UDTPausable.finalize_unpause");
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::finalize_unpause(tx, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.set_approvers" => {
+            debug!("program_entry_wrap | Entered UDTPausable.set_approvers");
+            let approvers_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | approvers_vec: {:?}", approvers_vec);
+
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let approval_threshold: u8 = u8::from_le_bytes(decode_hex(argv[3].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::set_approvers(tx, &approvers_vec, approval_threshold)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.propose_pause_update" => {
+            debug!("program_entry_wrap | Entered UDTPausable.propose_pause_update");
+            let pause_list_additions: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            let pause_list_removals: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[3].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | pause_list_additions: {:?}, pause_list_removals: {:?}", pause_list_additions, pause_list_removals);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::propose_pause_update(tx, &pause_list_additions, &pause_list_removals)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.approve_proposal" => {
+            debug!("program_entry_wrap | Entered UDTPausable.approve_proposal");
+            let proposal_out_point = OutPoint::from_slice(&decode_hex(argv[2].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
+            let approver_lock_hash: [u8; 32] = decode_hex(argv[3].as_ref())?.try_into().map_err(|_|Error::SSRIMethodsArgsInvalid)?;
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::approve_proposal(tx, proposal_out_point, approver_lock_hash)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.execute_proposal" => {
+            debug!("program_entry_wrap | Entered UDTPausable.execute_proposal");
+            let proposal_out_point = OutPoint::from_slice(&decode_hex(argv[2].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::execute_proposal(tx, proposal_out_point)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.add_redemption_locks" => {
+            debug!("program_entry_wrap | Entered UDTPausable.add_redemption_locks");
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+            let lock_hashes: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes: {:?}", lock_hashes);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::add_redemption_locks(tx, &lock_hashes)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.remove_redemption_locks" => {
+            debug!("program_entry_wrap | Entered UDTPausable.remove_redemption_locks");
+            if argv[2].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+            let lock_hashes: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes: {:?}", lock_hashes);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::remove_redemption_locks(tx, &lock_hashes)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.redeem" => {
+            debug!("program_entry_wrap | Entered UDTPausable.redeem");
+            if argv[2].is_empty() || argv[3].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+            let redemption_lock = Script::from_slice(&decode_hex(argv[2].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
+            let redeem_out_point_vec: Vec<OutPoint> = OutPointVec::from_slice(decode_hex(argv[3].as_ref())?.as_slice()).map_err(|_|Error::MoleculeVerificationError)?.into_iter().collect();
+            debug!("program_entry_wrap | redemption_lock: {:?}, redeem_out_point_vec: {:?}", redemption_lock, redeem_out_point_vec);
+
+            let tx = parse_optional_tx(&argv[1])?;
+
+            Ok(Cow::from(modules::PausableUDT::redeem(tx, redemption_lock, redeem_out_point_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.smt_pause" => {
+            debug!("program_entry_wrap | Entered UDTPausable.smt_pause");
+            let paused_lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[3].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[3].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+            Ok(Cow::from(modules::PausableUDT::smt_pause(tx, &paused_lock_hashes_vec, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.smt_unpause" => {
+            debug!("program_entry_wrap | Entered UDTPausable.smt_unpause");
+            let paused_lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            let lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[3].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+            debug!("program_entry_wrap | lock_hashes_vec: {:?}", lock_hashes_vec);
+
+            if argv[3].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx = parse_optional_tx(&argv[1])?;
+            Ok(Cow::from(modules::PausableUDT::smt_unpause(tx, &paused_lock_hashes_vec, &lock_hashes_vec)?.as_bytes().to_vec()))
+        },
+        "UDTPausable.attach_smt_proofs" => {
+            debug!("program_entry_wrap | Entered UDTPausable.attach_smt_proofs");
+            let paused_lock_hashes_vec: Vec<[u8; 32]> = decode_u8_32_vector(decode_hex(argv[2].as_ref())?).map_err(|_|error::Error::InvalidArray)?;
+
+            if argv[1].is_empty() {
+                Err(Error::SSRIMethodsArgsInvalid)?;
+            }
+
+            let tx: Transaction = Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?;
+            Ok(Cow::from(modules::PausableUDT::attach_smt_proofs(tx, &paused_lock_hashes_vec)?.as_bytes().to_vec()))
         },
     )?;
     let pipe = pipe()?;
@@ -1,28 +1,47 @@
+use crate::allowlist::{self, UDTAllowlistData};
+use crate::config::{
+    BurnPolicy, BURN_POLICY, MAX_TRANSFER_AMOUNT, UNPAUSE_DELAY_EPOCHS, WATCH_LIST_LOCK_HASHES,
+    WATCH_LIST_MAX_TRANSFER_AMOUNT,
+};
 use crate::error::Error;
-use crate::utils::{check_owner_mode, collect_inputs_amount, collect_outputs_amount};
+use crate::proposal::{self, UDTPauseProposalData};
+use crate::quota::{self, MinterQuota, UDTQuotaData};
+use crate::registry::{
+    FrozenCell, GlobalPause, LockCodeHashRule, LockCodeHashRules, PausableRegistryData,
+    PauseEntries, APPROVER_PLACEHOLDER_INDEX, RESTRICTION_BLOCK_SEND, RESTRICTION_FULL_FREEZE,
+    RESTRICTION_NONE,
+};
+use crate::roles::{self, UDTRolesData, ROLE_MINTER};
+use crate::supply::{self, UDTSupplyData};
+use crate::utils::{
+    calc_transfer_fee, calc_type_id, check_owner_mode, collect_amounts_by_lock,
+    collect_inputs_amount, collect_outputs_amount, current_epoch_lower_bound, encode_amount,
+    format_pause_list, get_recovery_lock, get_treasury_lock, parse_amount, script_like_to_script,
+    set_witness_args_type, verify_extension_data,
+};
 use crate::{get_pausable_data, DECIMALS, ICON, NAME, SYMBOL};
+use crate::{registry, smt};
 use alloc::borrow::ToOwned;
-use alloc::ffi::CString;
 use alloc::string::String;
-use alloc::vec;
 use alloc::vec::Vec;
-use ckb_hash::new_blake2b;
 use ckb_ssri_std::public_module_traits::udt::{UDTPausable, UDTPausableData, UDT};
 use ckb_ssri_std::utils::high_level::{
     find_cell_by_out_point, find_cell_data_by_out_point, find_out_point_by_type,
 };
 use ckb_ssri_std::utils::should_fallback;
 use ckb_std::ckb_constants::Source;
-use ckb_std::ckb_types::core::ScriptHashType;
 use ckb_std::ckb_types::packed::{
     Byte, Byte32, Byte32Vec, BytesVec, BytesVecBuilder, CellDep, CellDepVec, CellDepVecBuilder,
     CellInput, CellInputVec, CellInputVecBuilder, CellOutput, CellOutputBuilder,
-    CellOutputVecBuilder, RawTransactionBuilder, Script, ScriptBuilder, ScriptOptBuilder,
-    Transaction, TransactionBuilder, Uint32, Uint64,
+    CellOutputVecBuilder, OutPoint, RawTransactionBuilder, Script, ScriptOptBuilder, Transaction,
+    TransactionBuilder, Uint32, Uint64,
 };
 use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
 use ckb_std::debug;
-use ckb_std::high_level::{decode_hex, load_cell_data, load_cell_type, load_script};
+use ckb_std::high_level::{
+    load_cell_data, load_cell_lock_hash, load_cell_type, load_script, QueryIter,
+};
+use ckb_std::since::{EpochNumberWithFraction, Since};
 use serde_molecule::{from_slice, to_vec};
 
 pub struct PausableUDT;
@@ -72,32 +91,42 @@ impl UDT for PausableUDT {
             None => BytesVecBuilder::default(),
         };
 
+        let extension_data = Self::find_extension_data(&tx)?;
         for to_amount in to_amount_vec.iter() {
-            outputs_data_builder = outputs_data_builder.push(to_amount.pack().as_bytes().pack());
+            outputs_data_builder =
+                outputs_data_builder.push(encode_amount(*to_amount, &extension_data).pack());
         }
 
-        let mut cell_dep_vec_builder: CellDepVecBuilder = match tx {
-            Some(ref tx) => tx.clone().raw().cell_deps().as_builder(),
-            None => CellDepVecBuilder::default(),
-        };
-        let mut current_pausable_data = get_pausable_data()?;
-        while let Some(ref next_type_script_like) = current_pausable_data.next_type_script {
-            debug!("Next type script like: {:?}", next_type_script_like);
-            let next_type_script = ScriptBuilder::default()
-                .code_hash(next_type_script_like.clone().code_hash.pack())
-                .hash_type(Byte::new(next_type_script_like.clone().hash_type))
-                .args(next_type_script_like.clone().args.pack())
-                .build();
-            let cell_dep = CellDep::new_builder()
-                .out_point(find_out_point_by_type(next_type_script.clone())?)
-                .build();
-            cell_dep_vec_builder = cell_dep_vec_builder.push(cell_dep);
-            current_pausable_data = from_slice(
-                &find_cell_data_by_out_point(find_out_point_by_type(next_type_script.clone())?)?,
-                false,
-            )?;
+        // Pay the transfer fee to the treasury in an extra output
+        if let Some(treasury_lock) = get_treasury_lock()? {
+            let mut transfer_amount: u128 = 0;
+            for (to_lock, to_amount) in to_lock_vec.iter().zip(to_amount_vec.iter()) {
+                if *to_lock != treasury_lock {
+                    transfer_amount = transfer_amount
+                        .checked_add(*to_amount)
+                        .ok_or(Error::Overflow)?;
+                }
+            }
+            let fee_amount = calc_transfer_fee(transfer_amount);
+            if fee_amount > 0 {
+                cell_output_vec_builder = cell_output_vec_builder.push(
+                    CellOutputBuilder::default()
+                        .type_(
+                            ScriptOptBuilder::default()
+                                .set(Some(load_script()?))
+                                .build(),
+                        )
+                        .capacity(Uint64::default())
+                        .lock(treasury_lock)
+                        .build(),
+                );
+                outputs_data_builder =
+                    outputs_data_builder.push(encode_amount(fee_amount, &extension_data).pack());
+            }
         }
 
+        let cell_dep_vec = Self::build_registry_cell_deps(&tx)?;
+
         Ok(tx_builder
             .raw(
                 raw_tx_builder
@@ -106,7 +135,7 @@ impl UDT for PausableUDT {
                             .map(|t| t.raw().version())
                             .unwrap_or_else(|| Uint32::default()),
                     )
-                    .cell_deps(cell_dep_vec_builder.build())
+                    .cell_deps(cell_dep_vec)
                     .header_deps(
                         tx.clone()
                             .map(|t| t.raw().header_deps())
@@ -194,33 +223,19 @@ impl UDT for PausableUDT {
             None => BytesVecBuilder::default(),
         };
 
+        let extension_data = Self::find_extension_data(&tx)?;
         for to_amount in to_amount_vec.iter() {
-            outputs_data_builder = outputs_data_builder.push(to_amount.pack().as_bytes().pack());
+            outputs_data_builder =
+                outputs_data_builder.push(encode_amount(*to_amount, &extension_data).pack());
         }
 
-        let mut cell_dep_vec_builder: CellDepVecBuilder = match tx {
-            Some(ref tx) => tx.clone().raw().cell_deps().as_builder(),
-            None => CellDepVecBuilder::default(),
-        };
-        let mut current_pausable_data = get_pausable_data()?;
-        while let Some(ref next_type_script_like) = current_pausable_data.next_type_script {
-            debug!("Next type script like: {:?}", next_type_script_like);
-            let next_type_script = ScriptBuilder::default()
-                .code_hash(next_type_script_like.clone().code_hash.pack())
-                .hash_type(Byte::new(next_type_script_like.clone().hash_type))
-                .args(next_type_script_like.clone().args.pack())
-                .build();
-            let cell_dep = CellDep::new_builder()
-                .out_point(find_out_point_by_type(next_type_script.clone())?)
-                .build();
-            cell_dep_vec_builder = cell_dep_vec_builder.push(cell_dep);
-            current_pausable_data = from_slice(
-                &find_cell_data_by_out_point(find_out_point_by_type(next_type_script.clone())?)?,
-                false,
-            )?;
-        }
+        let cell_dep_vec = Self::build_registry_cell_deps(&tx)?;
 
-        Ok(tx_builder
+        let minted_amount = to_amount_vec
+            .iter()
+            .try_fold(0u128, |sum, amount| sum.checked_add(*amount))
+            .ok_or(Error::Overflow)?;
+        let tx = tx_builder
             .raw(
                 raw_tx_builder
                     .version(
@@ -228,7 +243,7 @@ impl UDT for PausableUDT {
                             .map(|t| t.raw().version())
                             .unwrap_or_else(|| Uint32::default()),
                     )
-                    .cell_deps(cell_dep_vec_builder.build())
+                    .cell_deps(cell_dep_vec)
                     .header_deps(
                         tx.clone()
                             .map(|t| t.raw().header_deps())
@@ -248,15 +263,23 @@ impl UDT for PausableUDT {
                     .map(|t| t.witnesses())
                     .unwrap_or_else(|| BytesVec::default()),
             )
-            .build())
+            .build();
+        let tx = Self::attach_supply_update(tx, minted_amount, 0)?;
+        Self::attach_quota_update(tx, minted_amount)
     }
 
     fn verify_mint() -> Result<(), Self::Error> {
         debug!("Entered UDT::verify_mint");
         let script = load_script()?;
         let args = script.args().unpack();
-        if check_owner_mode(&args)? {
-            return Ok(());
+        // With roles configured, minting requires the MINTER role instead of owner mode
+        let can_mint = match roles::load_roles()? {
+            Some(roles_data) => roles_data.inputs_have_role(ROLE_MINTER),
+            None => check_owner_mode(&args)?,
+        };
+        // Minters burning tokens still go through the burn policy
+        if can_mint || Self::is_quota_mint()? {
+            return Self::verify_burn();
         } else {
             return Err(Error::NoMintPermission);
         }
@@ -271,199 +294,11 @@ impl UDT for PausableUDT {
 impl UDTPausable for PausableUDT {
     // #[ssri_method(level = "script", transaction = true)]
     fn pause(tx: Option<Transaction>, lock_hashes: &Vec<[u8; 32]>) -> Result<Transaction, Error> {
-        let mut deduped_lock_hashes = lock_hashes.clone();
-        deduped_lock_hashes.dedup();
-        let tx_builder = match tx {
-            Some(ref tx) => tx.clone().as_builder(),
-            None => TransactionBuilder::default(),
-        };
-        let raw_tx_builder = match tx {
-            Some(ref tx) => tx.clone().raw().as_builder(),
-            None => RawTransactionBuilder::default(),
-        };
-
-        let mut new_cell_output: CellOutput;
-        let new_output_data: UDTPausableData;
-        let mut last_cell_type_script: Option<Script> = None;
-        let mut new_cell_input: Option<CellInput> = None;
-
-        debug!("Automatically redirect to the last pause list cell.");
-        let mut current_pausable_data = get_pausable_data()?;
-        // Dedup lock_hashes against current_pausable_data.pause_list
-        deduped_lock_hashes = deduped_lock_hashes
-            .into_iter()
-            .filter(|lock_hash| !current_pausable_data.pause_list.contains(lock_hash))
-            .collect();
-
-        while let Some(ref next_type_script_like) = current_pausable_data.next_type_script {
-            debug!("Next type script like: {:?}", next_type_script_like);
-            let next_type_script = ScriptBuilder::default()
-                .code_hash(next_type_script_like.clone().code_hash.pack())
-                .hash_type(Byte::new(next_type_script_like.clone().hash_type))
-                .args(next_type_script_like.clone().args.pack())
-                .build();
-            let next_cell_out_point = find_out_point_by_type(next_type_script.clone())?;
-            let next_pausable_data: UDTPausableData = from_slice(
-                &find_cell_data_by_out_point(next_cell_out_point.clone())?,
-                false,
-            )?;
-            // Dedup lock_hashes against next_pausable_data.pause_list
-            deduped_lock_hashes = deduped_lock_hashes
-                .into_iter()
-                .filter(|lock_hash| !next_pausable_data.pause_list.contains(lock_hash))
-                .collect();
-            if next_pausable_data.next_type_script.is_none() {
-                last_cell_type_script = Some(next_type_script.clone());
-            }
-            current_pausable_data = next_pausable_data;
-        }
-        if deduped_lock_hashes.len() == 0 {
-            return Err(Error::NothingToDo);
-        }
-        if last_cell_type_script.is_none() {
-            debug!("No pause list cell found. Try to generate the first pause list cell.");
-            match tx {
-                Some(ref tx) => match tx.raw().inputs().get(0) {
-                    Some(first_input_cell_input) => {
-                        let first_input_outpoint = first_input_cell_input.previous_output();
-                        let first_input_cell =
-                            find_cell_by_out_point(first_input_outpoint.clone())?;
-                        let mut hasher = new_blake2b();
-                        hasher.update(first_input_cell_input.as_slice());
-                        let type_id_index = tx.raw().outputs().len();
-                        hasher.update(&type_id_index.to_le_bytes());
-                        let mut ret = [0; 32];
-                        hasher.finalize(&mut ret);
-                        let type_id_bytes = ret.to_vec();
-                        let type_id_script_code_hash_string =
-                            "00000000000000000000000000000000000000000000000000545950455f4944";
-                        let cstring = CString::new(type_id_script_code_hash_string)
-                            .map_err(|_| Error::InvalidPauseData)?;
-                        let type_id_script_code_hash_cstr = &cstring.as_c_str();
-                        let new_type_id_script = Script::new_builder()
-                            .code_hash(
-                                Byte32::from_slice(&decode_hex(type_id_script_code_hash_cstr)?)
-                                    .map_err(|_| Error::MoleculeVerificationError)?,
-                            )
-                            .hash_type(ScriptHashType::Type.into())
-                            .args(type_id_bytes.clone().pack())
-                            .build();
-                        new_cell_output = CellOutput::new_builder()
-                            .lock(first_input_cell.lock())
-                            .type_(
-                                ScriptOptBuilder::default()
-                                    .set(Some(new_type_id_script))
-                                    .build(),
-                            )
-                            .build();
-                        new_output_data = UDTPausableData {
-                            pause_list: deduped_lock_hashes.clone(),
-                            next_type_script: None,
-                        };
-                    }
-                    None => return Err(Error::NoPausePermission),
-                },
-                None => return Err(Error::NoPausePermission),
-            }
-        } else {
-            let last_cell_out_point = find_out_point_by_type(last_cell_type_script.should_be_ok())?;
-            new_cell_output = find_cell_by_out_point(last_cell_out_point.clone())?;
-            new_cell_output = new_cell_output
-                .as_builder()
-                .capacity(Uint64::default())
-                .build();
-            let last_cell_data = find_cell_data_by_out_point(last_cell_out_point.clone())?;
-            let mut pausable_data: UDTPausableData = from_slice(&last_cell_data, false)?;
-            pausable_data.pause_list.extend(deduped_lock_hashes);
-            new_output_data = pausable_data;
-            new_cell_input = Some(
-                CellInput::new_builder()
-                    .previous_output(last_cell_out_point)
-                    .build(),
-            );
-        }
-
-        let cell_output_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
-            None => CellOutputVecBuilder::default(),
-        }
-        .push(new_cell_output);
-
-        let output_data_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
-            None => BytesVecBuilder::default(),
-        }
-        .push(to_vec(&new_output_data, false)?.pack());
-
-        let mut input_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().inputs().as_builder(),
-            None => CellInputVecBuilder::default(),
-        };
-
-        match new_cell_input {
-            Some(new_cell_input) => {
-                input_vec_builder = input_vec_builder.push(new_cell_input);
-            }
-            None => {}
-        }
-
-        return Ok(tx_builder
-            .raw(
-                raw_tx_builder
-                    .version(
-                        tx.clone()
-                            .map(|t| t.raw().version())
-                            .unwrap_or_else(|| Uint32::default()),
-                    )
-                    .cell_deps(
-                        tx.clone()
-                            .map(|t| t.raw().cell_deps())
-                            .unwrap_or_else(|| CellDepVec::default()),
-                    )
-                    .header_deps(
-                        tx.clone()
-                            .map(|t| t.raw().header_deps())
-                            .unwrap_or_else(|| Byte32Vec::default()),
-                    )
-                    .inputs(input_vec_builder.build())
-                    .outputs(cell_output_vec_builder.build())
-                    .outputs_data(output_data_vec_builder.build())
-                    .build(),
-            )
-            .witnesses(
-                tx.clone()
-                    .map(|t| t.witnesses())
-                    .unwrap_or_else(|| BytesVec::default()),
-            )
-            .build());
+        Self::pause_with_level(tx, lock_hashes, RESTRICTION_FULL_FREEZE, None)
     }
 
     // #[ssri_method(level = "script", transaction = true)]
     fn unpause(tx: Option<Transaction>, lock_hashes: &Vec<[u8; 32]>) -> Result<Transaction, Error> {
-        let tx_builder = match tx {
-            Some(ref tx) => tx.clone().as_builder(),
-            None => TransactionBuilder::default(),
-        };
-        let raw_tx_builder = match tx {
-            Some(ref tx) => tx.clone().raw().as_builder(),
-            None => RawTransactionBuilder::default(),
-        };
-
-        let mut new_cell_output: CellOutput;
-        let mut new_cell_input: Option<CellInput>;
-        let mut output_data_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
-            None => BytesVecBuilder::default(),
-        };
-        let mut input_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().inputs().as_builder(),
-            None => CellInputVecBuilder::default(),
-        };
-        let mut cell_output_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
-            None => CellOutputVecBuilder::default(),
-        };
-
         let in_contract_pausable_data = get_pausable_data()?;
         if in_contract_pausable_data
             .pause_list
@@ -473,151 +308,27 @@ impl UDTPausable for PausableUDT {
         {
             return Err(Error::NoUnpausePermission)?;
         }
-        let mut current_pausable_data = in_contract_pausable_data.clone();
-        while current_pausable_data.next_type_script.is_some() {
-            let script_like = current_pausable_data
-                .next_type_script
-                .as_ref()
-                .should_be_ok();
-            let next_type_script = Script::new_builder()
-                .code_hash(script_like.code_hash.pack())
-                .hash_type(Byte::new(script_like.hash_type))
-                .args(script_like.args.pack())
-                .build();
-            let next_pausable_data_outpoint = find_out_point_by_type(next_type_script)?;
-            let next_pausable_data_outpoint_data =
-                find_cell_data_by_out_point(next_pausable_data_outpoint.clone())?;
-            let next_pausable_data: UDTPausableData =
-                from_slice(&next_pausable_data_outpoint_data, false)?;
-            if next_pausable_data
-                .pause_list
-                .iter()
-                .find(|x| lock_hashes.contains(x))
-                .is_some()
-            {
-                new_cell_input = Some(
-                    CellInput::new_builder()
-                        .previous_output(next_pausable_data_outpoint.clone())
-                        .build(),
-                );
-                new_cell_output = find_cell_by_out_point(next_pausable_data_outpoint.clone())?;
-                new_cell_output = new_cell_output
-                    .as_builder()
-                    .capacity(Uint64::default())
-                    .build();
-                input_vec_builder = input_vec_builder.push(new_cell_input.should_be_ok());
-                cell_output_vec_builder = cell_output_vec_builder.push(new_cell_output);
-                let mut new_pausable_data = next_pausable_data.clone();
-                new_pausable_data
-                    .pause_list
-                    .retain(|x| !lock_hashes.contains(x));
-                output_data_vec_builder =
-                    output_data_vec_builder.push(to_vec(&new_pausable_data, false)?.pack());
+        Self::build_registry_update(tx, |pausable_data, _| {
+            if !pausable_data.contains_any(lock_hashes) {
+                return Ok(false);
             }
-            current_pausable_data = next_pausable_data;
-        }
-
-        let cell_outputs = cell_output_vec_builder.build();
-        if cell_outputs.len() == 0 {
-            return Err(Error::NothingToDo);
-        }
-
-        return Ok(tx_builder
-            .raw(
-                raw_tx_builder
-                    .version(
-                        tx.clone()
-                            .map(|t| t.raw().version())
-                            .unwrap_or_else(|| Uint32::default()),
-                    )
-                    .cell_deps(
-                        tx.clone()
-                            .map(|t| t.raw().cell_deps())
-                            .unwrap_or_else(|| CellDepVec::default()),
-                    )
-                    .header_deps(
-                        tx.clone()
-                            .map(|t| t.raw().header_deps())
-                            .unwrap_or_else(|| Byte32Vec::default()),
-                    )
-                    .inputs(input_vec_builder.build())
-                    .outputs(cell_output_vec_builder.build())
-                    .outputs_data(output_data_vec_builder.build())
-                    .build(),
-            )
-            .witnesses(
-                tx.clone()
-                    .map(|t| t.witnesses())
-                    .unwrap_or_else(|| BytesVec::default()),
-            )
-            .build());
+            pausable_data.remove_restrictions(lock_hashes);
+            Ok(true)
+        })
     }
 
     // #[ssri_method(level = "script")]
     fn is_paused(lock_hashes: &Vec<[u8; 32]>) -> Result<Vec<bool>, Error> {
         debug!("Entered is_paused");
-        debug!("lock_hashes: {:?}", lock_hashes);
-        // By default all not paused
-        let mut result = vec![false; lock_hashes.len()];
-
-        let mut current_pausable_data = get_pausable_data()?;
-        let mut seen_type_hashes: Vec<Byte32> = Vec::new();
-
-        loop {
-            // Check current pausable data's pause list
-            for (idx, lock_hash) in lock_hashes.iter().enumerate() {
-                if current_pausable_data.pause_list.contains(lock_hash) {
-                    result[idx] = true;
-                }
-            }
-
-            // Check for next type script in the chain
-            match current_pausable_data.next_type_script {
-                Some(next_type_script) => {
-                    let next_type_script = Script::new_builder()
-                        .code_hash(next_type_script.code_hash.pack())
-                        .hash_type(Byte::new(next_type_script.hash_type))
-                        .args(next_type_script.args.pack())
-                        .build();
-
-                    // Prevent infinite loops
-                    if seen_type_hashes.contains(&next_type_script.calc_script_hash()) {
-                        return Err(Error::CyclicPauseList);
-                    }
-                    seen_type_hashes.push(next_type_script.calc_script_hash());
-
-                    // Load next pausable data
-                    current_pausable_data = match should_fallback()? {
-                        true => {
-                            // Fallback logic to find next pausable data in cell deps
-                            let mut index = 0;
-                            loop {
-                                match load_cell_type(index, Source::CellDep) {
-                                    Ok(Some(next_pausable_cell_type_script))
-                                        if next_pausable_cell_type_script == next_type_script =>
-                                    {
-                                        break from_slice(
-                                            &load_cell_data(index, Source::CellDep)?,
-                                            false,
-                                        )?
-                                    }
-                                    Ok(Some(_)) | Ok(None) => index += 1,
-                                    Err(_) => return Err(Error::IncompletePauseList),
-                                }
-                            }
-                        }
-                        false => {
-                            // SSRI way to find next pausable data
-                            let next_out_point = find_out_point_by_type(next_type_script)?;
-                            from_slice(&find_cell_data_by_out_point(next_out_point)?, false)?
-                        }
-                    };
-                }
-                None => break, // No more pausable data cells
-            }
-        }
-
-        Ok(result)
+        // Expiries can only be checked against the transaction on-chain
+        let current_epoch = match should_fallback()? {
+            true => current_epoch_lower_bound()?,
+            false => None,
+        };
+        Ok(Self::restriction_levels(lock_hashes, current_epoch)?
+            .into_iter()
+            .map(|level| level != RESTRICTION_NONE)
+            .collect())
     }
 
     // #[ssri_method(level = "script")]
@@ -776,3 +487,2028 @@ impl UDTPausable for PausableUDT {
         Ok(paused_byte32_vec_builder.build())
     }
 }
+
+impl PausableUDT {
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn burn(
+        tx: Option<Transaction>,
+        burn_out_points: Vec<OutPoint>,
+        burn_amount: u128,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDT::burn");
+        if let BurnPolicy::Disabled = BURN_POLICY {
+            return Err(Error::NoBurnPermission);
+        }
+        if burn_out_points.is_empty() || burn_amount == 0 {
+            return Err(Error::SSRIMethodsArgsInvalid);
+        }
+        let tx_builder = match tx {
+            Some(ref tx) => tx.clone().as_builder(),
+            None => TransactionBuilder::default(),
+        };
+        let raw_tx_builder = match tx {
+            Some(ref tx) => tx.clone().raw().as_builder(),
+            None => RawTransactionBuilder::default(),
+        };
+
+        let mut input_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().inputs().as_builder(),
+            None => CellInputVecBuilder::default(),
+        };
+        let mut inputs_amount: u128 = 0;
+        let mut change_extension_data: Option<Vec<u8>> = None;
+        for burn_out_point in burn_out_points.iter() {
+            let burn_cell_data = find_cell_data_by_out_point(burn_out_point.clone())?;
+            let (burn_cell_amount, extension_data) = parse_amount(&burn_cell_data)?;
+            change_extension_data.get_or_insert_with(|| extension_data.to_vec());
+            inputs_amount = inputs_amount
+                .checked_add(burn_cell_amount)
+                .ok_or(Error::Overflow)?;
+            input_vec_builder = input_vec_builder.push(
+                CellInput::new_builder()
+                    .previous_output(burn_out_point.clone())
+                    .build(),
+            );
+        }
+        if inputs_amount < burn_amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let mut cell_output_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
+            None => CellOutputVecBuilder::default(),
+        };
+        let mut outputs_data_builder = match tx {
+            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
+            None => BytesVecBuilder::default(),
+        };
+
+        // The change goes back to the lock of the first burnt cell, with its extension data
+        let change_amount = inputs_amount - burn_amount;
+        if change_amount > 0 {
+            let first_burn_cell = find_cell_by_out_point(burn_out_points[0].clone())?;
+            let change_output = CellOutputBuilder::default()
+                .type_(
+                    ScriptOptBuilder::default()
+                        .set(Some(load_script()?))
+                        .build(),
+                )
+                .capacity(Uint64::default())
+                .lock(first_burn_cell.lock())
+                .build();
+            cell_output_vec_builder = cell_output_vec_builder.push(change_output);
+            outputs_data_builder = outputs_data_builder.push(
+                encode_amount(change_amount, &change_extension_data.unwrap_or_default()).pack(),
+            );
+        }
+
+        let cell_dep_vec = Self::build_registry_cell_deps(&tx)?;
+
+        let tx = tx_builder
+            .raw(
+                raw_tx_builder
+                    .version(tx.clone().map(|t| t.raw().version()).unwrap_or_default())
+                    .cell_deps(cell_dep_vec)
+                    .header_deps(
+                        tx.clone()
+                            .map(|t| t.raw().header_deps())
+                            .unwrap_or_default(),
+                    )
+                    .inputs(input_vec_builder.build())
+                    .outputs(cell_output_vec_builder.build())
+                    .outputs_data(outputs_data_builder.build())
+                    .build(),
+            )
+            .witnesses(tx.clone().map(|t| t.witnesses()).unwrap_or_default())
+            .build();
+        Self::attach_supply_update(tx, 0, burn_amount)
+    }
+
+    /// Returns the extension data of the first token input of `tx`, so that built outputs keep the same extension.
+    fn find_extension_data(tx: &Option<Transaction>) -> Result<Vec<u8>, Error> {
+        let tx = match tx {
+            Some(tx) => tx,
+            None => return Ok(Vec::new()),
+        };
+        let script = load_script()?;
+        for input in tx.raw().inputs().into_iter() {
+            let input_cell = find_cell_by_out_point(input.previous_output())?;
+            if input_cell.type_().to_opt().as_ref() == Some(&script) {
+                let input_cell_data = find_cell_data_by_out_point(input.previous_output())?;
+                let (_, extension_data) = parse_amount(&input_cell_data)?;
+                verify_extension_data(extension_data)?;
+                return Ok(extension_data.to_vec());
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    // #[ssri_method(level = "script")]
+    pub fn total_supply() -> Result<u128, Error> {
+        debug!("Entered UDT::total_supply");
+        let supply_script = supply::get_supply_script()?.ok_or(Error::SSRIMethodsNotImplemented)?;
+        let supply_data: UDTSupplyData = from_slice(
+            &find_cell_data_by_out_point(find_out_point_by_type(supply_script)?)?,
+            false,
+        )?;
+        Ok(supply_data.total_supply)
+    }
+
+    // #[ssri_method(level = "script")]
+    pub fn roles() -> Result<UDTRolesData, Error> {
+        debug!("Entered UDT::roles");
+        roles::load_roles()?.ok_or(Error::SSRIMethodsNotImplemented)
+    }
+
+    // #[ssri_method(level = "script")]
+    pub fn has_role(role: u8, lock_hash: &[u8; 32]) -> Result<bool, Error> {
+        debug!("Entered UDT::has_role");
+        Ok(Self::roles()?.has_role(role, lock_hash))
+    }
+
+    /// Replaces the data of the roles cell. Before roles are configured, the roles cell is created with the Type ID and the lock of the first input of `tx`, which must be in owner mode.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn update_roles(
+        tx: Option<Transaction>,
+        mut roles_data: UDTRolesData,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDT::update_roles");
+        roles_data
+            .roles
+            .sort_by_key(|role_members| role_members.role);
+        for role_members in roles_data.roles.iter_mut() {
+            role_members.lock_hashes.sort();
+            role_members.lock_hashes.dedup();
+        }
+        let tx = tx.ok_or(Error::NoRolePermission)?;
+        let mut input_vec_builder = tx.raw().inputs().as_builder();
+        let roles_output = match roles::get_roles_script()? {
+            Some(roles_script) => {
+                let roles_out_point = find_out_point_by_type(roles_script)?;
+                input_vec_builder = input_vec_builder.push(
+                    CellInput::new_builder()
+                        .previous_output(roles_out_point.clone())
+                        .build(),
+                );
+                find_cell_by_out_point(roles_out_point)?
+                    .as_builder()
+                    .capacity(Uint64::default())
+                    .build()
+            }
+            None => {
+                let first_input_cell_input =
+                    tx.raw().inputs().get(0).ok_or(Error::NoRolePermission)?;
+                let first_input_cell =
+                    find_cell_by_out_point(first_input_cell_input.previous_output())?;
+                let type_id =
+                    calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+                CellOutput::new_builder()
+                    .lock(first_input_cell.lock())
+                    .type_(
+                        ScriptOptBuilder::default()
+                            .set(Some(roles::build_roles_script(&load_script()?, type_id)?))
+                            .build(),
+                    )
+                    .build()
+            }
+        };
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(input_vec_builder.build())
+                    .outputs(tx.raw().outputs().as_builder().push(roles_output).build())
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&roles_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Creates the supply cell with the Type ID of the first input of `tx`, which must be in owner mode. `total_supply` accounts for tokens issued before tracking starts. Holders update the supply cell when they burn, so `supply_lock` should be a lock anyone can unlock.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn create_supply_cell(
+        tx: Option<Transaction>,
+        supply_lock: Script,
+        total_supply: u128,
+        max_supply: u128,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDT::create_supply_cell");
+        if total_supply > max_supply {
+            return Err(Error::ExceededMaxSupply);
+        }
+        let tx = tx.ok_or(Error::NoMintPermission)?;
+        let first_input_cell_input = tx.raw().inputs().get(0).ok_or(Error::NoMintPermission)?;
+        let type_id = calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+        let script = load_script()?;
+        let supply_data = UDTSupplyData {
+            udt_type_hash: script.calc_script_hash().unpack(),
+            total_supply,
+            max_supply,
+        };
+        let supply_output = CellOutput::new_builder()
+            .lock(supply_lock)
+            .type_(
+                ScriptOptBuilder::default()
+                    .set(Some(supply::build_supply_script(&script, type_id)?))
+                    .build(),
+            )
+            .build();
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .outputs(tx.raw().outputs().as_builder().push(supply_output).build())
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&supply_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// With quotas configured, a minter with a quota among the inputs may mint. The quota cell checks the amount against the quota.
+    fn is_quota_mint() -> Result<bool, Error> {
+        if quota::get_quota_script()?.is_none()
+            || collect_outputs_amount()? <= collect_inputs_amount()?
+        {
+            return Ok(false);
+        }
+        Ok(quota::load_input_quota_data()?
+            .and_then(|quota_data| quota_data.find_input_minter())
+            .is_some())
+    }
+
+    /// Returns the remaining mint quota of `lock_hash`, or 0 when it has none.
+    // #[ssri_method(level = "script")]
+    pub fn remaining_quota(lock_hash: &[u8; 32]) -> Result<u128, Error> {
+        debug!("Entered UDT::remaining_quota");
+        let quota_script = quota::get_quota_script()?.ok_or(Error::SSRIMethodsNotImplemented)?;
+        let quota_data: UDTQuotaData = from_slice(
+            &find_cell_data_by_out_point(find_out_point_by_type(quota_script)?)?,
+            false,
+        )?;
+        Ok(quota_data.remaining_quota(lock_hash).unwrap_or_default())
+    }
+
+    /// Replaces the mint quotas, which requires owner mode. Without a configured quota cell, it is created with the Type ID and the lock of the first input of `tx`.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn set_mint_quotas(
+        tx: Option<Transaction>,
+        mut quotas: Vec<MinterQuota>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDT::set_mint_quotas");
+        let tx = tx.ok_or(Error::NoMintPermission)?;
+        quotas.sort_by_key(|quota| quota.lock_hash);
+        if quotas
+            .windows(2)
+            .any(|pair| pair[0].lock_hash == pair[1].lock_hash)
+        {
+            return Err(Error::SSRIMethodsArgsInvalid);
+        }
+        let script = load_script()?;
+        let quota_data = UDTQuotaData {
+            udt_type_hash: script.calc_script_hash().unpack(),
+            quotas,
+        };
+
+        let mut input_vec_builder = tx.raw().inputs().as_builder();
+        let quota_output = match quota::get_quota_script()? {
+            Some(quota_script) => {
+                let quota_out_point = find_out_point_by_type(quota_script)?;
+                input_vec_builder = input_vec_builder.push(
+                    CellInput::new_builder()
+                        .previous_output(quota_out_point.clone())
+                        .build(),
+                );
+                find_cell_by_out_point(quota_out_point)?
+                    .as_builder()
+                    .capacity(Uint64::default())
+                    .build()
+            }
+            None => {
+                let first_input_cell_input =
+                    tx.raw().inputs().get(0).ok_or(Error::NoMintPermission)?;
+                let first_input_cell =
+                    find_cell_by_out_point(first_input_cell_input.previous_output())?;
+                let type_id =
+                    calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+                CellOutput::new_builder()
+                    .lock(first_input_cell.lock())
+                    .type_(
+                        ScriptOptBuilder::default()
+                            .set(Some(quota::build_quota_script(&script, type_id)?))
+                            .build(),
+                    )
+                    .build()
+            }
+        };
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(input_vec_builder.build())
+                    .outputs(tx.raw().outputs().as_builder().push(quota_output).build())
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&quota_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Adds the update of the quota cell for `minted_amount` to `tx` when quotas are enabled. The quota of the first input lock that has one is spent, owner mode included.
+    fn attach_quota_update(tx: Transaction, minted_amount: u128) -> Result<Transaction, Error> {
+        let quota_script = match quota::get_quota_script()? {
+            Some(script) => script,
+            None => return Ok(tx),
+        };
+        let quota_out_point = find_out_point_by_type(quota_script)?;
+        let mut quota_data: UDTQuotaData = from_slice(
+            &find_cell_data_by_out_point(quota_out_point.clone())?,
+            false,
+        )?;
+        let mut minter_quota = None;
+        for input in tx.raw().inputs().into_iter() {
+            let lock_hash: [u8; 32] = find_cell_by_out_point(input.previous_output())?
+                .lock()
+                .calc_script_hash()
+                .unpack();
+            minter_quota = quota_data
+                .quotas
+                .iter_mut()
+                .find(|quota| quota.lock_hash == lock_hash);
+            if minter_quota.is_some() {
+                break;
+            }
+        }
+        let minter_quota = minter_quota.ok_or(Error::ExceededMintQuota)?;
+        minter_quota.remaining_quota = minter_quota
+            .remaining_quota
+            .checked_sub(minted_amount)
+            .ok_or(Error::ExceededMintQuota)?;
+        let quota_output = find_cell_by_out_point(quota_out_point.clone())?
+            .as_builder()
+            .capacity(Uint64::default())
+            .build();
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(
+                        tx.raw()
+                            .inputs()
+                            .as_builder()
+                            .push(
+                                CellInput::new_builder()
+                                    .previous_output(quota_out_point)
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .outputs(tx.raw().outputs().as_builder().push(quota_output).build())
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&quota_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Adds the update of the supply cell for `minted_amount` and `burnt_amount` to `tx` when supply tracking is enabled.
+    fn attach_supply_update(
+        tx: Transaction,
+        minted_amount: u128,
+        burnt_amount: u128,
+    ) -> Result<Transaction, Error> {
+        let supply_script = match supply::get_supply_script()? {
+            Some(script) => script,
+            None => return Ok(tx),
+        };
+        let supply_out_point = find_out_point_by_type(supply_script)?;
+        let mut supply_data: UDTSupplyData = from_slice(
+            &find_cell_data_by_out_point(supply_out_point.clone())?,
+            false,
+        )?;
+        supply_data.total_supply = supply_data
+            .total_supply
+            .checked_add(minted_amount)
+            .and_then(|total_supply| total_supply.checked_sub(burnt_amount))
+            .ok_or(Error::InvalidSupplyData)?;
+        if supply_data.total_supply > supply_data.max_supply {
+            return Err(Error::ExceededMaxSupply);
+        }
+        let supply_output = find_cell_by_out_point(supply_out_point.clone())?
+            .as_builder()
+            .capacity(Uint64::default())
+            .build();
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(
+                        tx.raw()
+                            .inputs()
+                            .as_builder()
+                            .push(
+                                CellInput::new_builder()
+                                    .previous_output(supply_out_point)
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .outputs(tx.raw().outputs().as_builder().push(supply_output).build())
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&supply_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Applies `BURN_POLICY` when the token outputs hold less than the token inputs, for minters and owner mode as well.
+    pub fn verify_burn() -> Result<(), Error> {
+        debug!("Entered UDT::verify_burn");
+        let inputs_amount = collect_inputs_amount()?;
+        let outputs_amount = collect_outputs_amount()?;
+        if inputs_amount <= outputs_amount {
+            return Ok(());
+        }
+        debug!("burn_amount: {}", inputs_amount - outputs_amount);
+        match BURN_POLICY {
+            BurnPolicy::Holders => Ok(()),
+            BurnPolicy::OwnerOnly => {
+                let args: Bytes = load_script()?.args().unpack();
+                if check_owner_mode(&args)? {
+                    Ok(())
+                } else {
+                    Err(Error::NoBurnPermission)
+                }
+            }
+            BurnPolicy::Disabled => Err(Error::NoBurnPermission),
+        }
+    }
+
+    /// Charges the transfer fee on the net amount input locks send away to other locks, i.e. what the locks other than the treasury gain in the transaction, so that a recipient adding its own input still counts as receiving. The treasury spending itself pays no fee.
+    pub fn verify_transfer_fee() -> Result<(), Error> {
+        debug!("Entered verify_transfer_fee");
+        if let Some(treasury_lock) = get_treasury_lock()? {
+            let treasury_lock_hash: [u8; 32] = treasury_lock.calc_script_hash().unpack();
+            let mut transfer_amount: u128 = 0;
+            let mut fee_amount: u128 = 0;
+            for (lock_hash, inputs_amount, outputs_amount) in collect_amounts_by_lock()? {
+                if lock_hash == treasury_lock_hash {
+                    if inputs_amount > 0 {
+                        return Ok(());
+                    }
+                    fee_amount = outputs_amount;
+                } else {
+                    transfer_amount = transfer_amount
+                        .checked_add(outputs_amount.saturating_sub(inputs_amount))
+                        .ok_or(Error::Overflow)?;
+                }
+            }
+            debug!(
+                "transfer_amount: {}, fee_amount: {}",
+                transfer_amount, fee_amount
+            );
+            if fee_amount < calc_transfer_fee(transfer_amount) {
+                return Err(Error::InsufficientTransferFee);
+            }
+        }
+        Ok(())
+    }
+
+    /// Caps the net outflow of the transaction: what each input lock loses (its token inputs minus its token outputs), summed over the locks that lose tokens. Sending back to an input lock is change, and a recipient adding its own input does not hide what it receives. The watch list limit applies instead when any token input uses a watched lock.
+    pub fn verify_transfer_limit() -> Result<(), Error> {
+        debug!("Entered verify_transfer_limit");
+        let input_lock_hashes: Vec<[u8; 32]> =
+            QueryIter::new(load_cell_lock_hash, Source::GroupInput).collect();
+        let watch_list = format_pause_list(WATCH_LIST_LOCK_HASHES.to_vec());
+        let max_transfer_amount = match input_lock_hashes
+            .iter()
+            .any(|lock_hash| watch_list.contains(lock_hash))
+        {
+            true if WATCH_LIST_MAX_TRANSFER_AMOUNT != 0 => WATCH_LIST_MAX_TRANSFER_AMOUNT,
+            _ => MAX_TRANSFER_AMOUNT,
+        };
+        if max_transfer_amount == 0 {
+            return Ok(());
+        }
+
+        let mut transfer_amount: u128 = 0;
+        for (_, inputs_amount, outputs_amount) in collect_amounts_by_lock()? {
+            transfer_amount = transfer_amount
+                .checked_add(inputs_amount.saturating_sub(outputs_amount))
+                .ok_or(Error::Overflow)?;
+        }
+        debug!(
+            "transfer_amount: {}, max_transfer_amount: {}",
+            transfer_amount, max_transfer_amount
+        );
+        if transfer_amount > max_transfer_amount {
+            return Err(Error::ExceededTransferLimit);
+        }
+        Ok(())
+    }
+
+    /// Loads the in-contract pausable data followed by every external pause list cell of the chain, from `CellDep` on-chain or through SSRI otherwise.
+    pub fn load_registry_chain() -> Result<Vec<PausableRegistryData>, Error> {
+        debug!("Entered load_registry_chain");
+        let mut chain: Vec<PausableRegistryData> = Vec::new();
+        let mut current_pausable_data: PausableRegistryData = get_pausable_data()?.into();
+        let mut seen_type_hashes: Vec<Byte32> = Vec::new();
+
+        loop {
+            let next_type_script = current_pausable_data
+                .next_type_script
+                .as_ref()
+                .map(script_like_to_script);
+            chain.push(current_pausable_data);
+            let next_type_script = match next_type_script {
+                Some(next_type_script) => next_type_script,
+                None => break, // No more pausable data cells
+            };
+
+            // Prevent infinite loops
+            if seen_type_hashes.contains(&next_type_script.calc_script_hash()) {
+                return Err(Error::CyclicPauseList);
+            }
+            seen_type_hashes.push(next_type_script.calc_script_hash());
+
+            // Load next pausable data
+            current_pausable_data = match should_fallback()? {
+                true => {
+                    // Fallback logic to find next pausable data in cell deps
+                    let index = QueryIter::new(load_cell_type, Source::CellDep)
+                        .position(|type_script| type_script.as_ref() == Some(&next_type_script))
+                        .ok_or(Error::IncompletePauseList)?;
+                    registry::decode_registry_data(&load_cell_data(index, Source::CellDep)?)?
+                }
+                false => {
+                    // SSRI way to find next pausable data
+                    let next_out_point = find_out_point_by_type(next_type_script)?;
+                    registry::decode_registry_data(&find_cell_data_by_out_point(next_out_point)?)?
+                }
+            };
+        }
+
+        Ok(chain)
+    }
+
+    /// Finds the out point and data of every external pause list cell of the chain through SSRI.
+    fn find_registry_cells() -> Result<Vec<(OutPoint, PausableRegistryData)>, Error> {
+        let mut registry_cells: Vec<(OutPoint, PausableRegistryData)> = Vec::new();
+        let mut next_type_script_like = get_pausable_data()?.next_type_script;
+        let mut seen_type_hashes: Vec<Byte32> = Vec::new();
+        while let Some(ref script_like) = next_type_script_like {
+            let next_type_script = script_like_to_script(script_like);
+            if seen_type_hashes.contains(&next_type_script.calc_script_hash()) {
+                return Err(Error::CyclicPauseList);
+            }
+            seen_type_hashes.push(next_type_script.calc_script_hash());
+
+            let next_out_point = find_out_point_by_type(next_type_script)?;
+            let next_pausable_data = registry::decode_registry_data(&find_cell_data_by_out_point(
+                next_out_point.clone(),
+            )?)?;
+            next_type_script_like = next_pausable_data.next_type_script.clone();
+            registry_cells.push((next_out_point, next_pausable_data));
+        }
+        Ok(registry_cells)
+    }
+
+    /// Returns the cell deps of `tx` followed by one for every external pause list cell of the chain, found through SSRI.
+    fn build_registry_cell_deps(tx: &Option<Transaction>) -> Result<CellDepVec, Error> {
+        let mut cell_dep_vec_builder: CellDepVecBuilder = match tx {
+            Some(tx) => tx.raw().cell_deps().as_builder(),
+            None => CellDepVecBuilder::default(),
+        };
+        for (out_point, _) in Self::find_registry_cells()? {
+            cell_dep_vec_builder =
+                cell_dep_vec_builder.push(CellDep::new_builder().out_point(out_point).build());
+        }
+        Ok(cell_dep_vec_builder.build())
+    }
+
+    /// Builds a transaction rewriting every external pause list cell for which `update` returns true. `update` is told whether the cell is the last one of the chain. Without any external pause list cell, the first one is created from the first input of `tx` and counts as the last. When a rewritten cell has an approval threshold, one placeholder input (`APPROVER_PLACEHOLDER_INDEX`) is added per required approver, which the caller must replace with a live cell of that approver before the transaction can be committed. This applies to every pause list builder.
+    fn build_registry_update<F>(
+        tx: Option<Transaction>,
+        mut update: F,
+    ) -> Result<Transaction, Error>
+    where
+        F: FnMut(&mut PausableRegistryData, bool) -> Result<bool, Error>,
+    {
+        let tx_builder = match tx {
+            Some(ref tx) => tx.clone().as_builder(),
+            None => TransactionBuilder::default(),
+        };
+        let raw_tx_builder = match tx {
+            Some(ref tx) => tx.clone().raw().as_builder(),
+            None => RawTransactionBuilder::default(),
+        };
+        let mut input_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().inputs().as_builder(),
+            None => CellInputVecBuilder::default(),
+        };
+        let mut cell_output_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
+            None => CellOutputVecBuilder::default(),
+        };
+        let mut output_data_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
+            None => BytesVecBuilder::default(),
+        };
+        let mut updated_cells_count = 0;
+
+        debug!("Automatically redirect to the pause list cells.");
+        let registry_cells = Self::find_registry_cells()?;
+        if registry_cells.is_empty() {
+            debug!("No pause list cell found. Try to generate the first pause list cell.");
+            let mut new_output_data = PausableRegistryData::default();
+            if !update(&mut new_output_data, true)? {
+                return Err(Error::NothingToDo);
+            }
+            let first_input_cell_input = tx
+                .as_ref()
+                .and_then(|tx| tx.raw().inputs().get(0))
+                .ok_or(Error::NoPausePermission)?;
+            let first_input_cell =
+                find_cell_by_out_point(first_input_cell_input.previous_output())?;
+            let type_id = calc_type_id(
+                &first_input_cell_input,
+                tx.as_ref()
+                    .map(|tx| tx.raw().outputs().len())
+                    .unwrap_or_default() as u64,
+            );
+            let new_registry_script = registry::build_registry_script(&load_script()?, type_id)?;
+            cell_output_vec_builder = cell_output_vec_builder.push(
+                CellOutput::new_builder()
+                    .lock(first_input_cell.lock())
+                    .type_(
+                        ScriptOptBuilder::default()
+                            .set(Some(new_registry_script))
+                            .build(),
+                    )
+                    .build(),
+            );
+            output_data_vec_builder =
+                output_data_vec_builder.push(to_vec(&new_output_data, false)?.pack());
+            updated_cells_count += 1;
+        }
+
+        let mut approver_lock_hashes: Vec<[u8; 32]> = Vec::new();
+        let last_index = registry_cells.len().saturating_sub(1);
+        for (index, (out_point, pausable_data)) in registry_cells.into_iter().enumerate() {
+            let mut new_pausable_data = pausable_data.clone();
+            if !update(&mut new_pausable_data, index == last_index)? {
+                continue;
+            }
+            approver_lock_hashes.extend(
+                pausable_data
+                    .approvers
+                    .iter()
+                    .take(pausable_data.approval_threshold as usize),
+            );
+            let new_cell_output = find_cell_by_out_point(out_point.clone())?
+                .as_builder()
+                .capacity(Uint64::default())
+                .build();
+            input_vec_builder =
+                input_vec_builder.push(CellInput::new_builder().previous_output(out_point).build());
+            cell_output_vec_builder = cell_output_vec_builder.push(new_cell_output);
+            output_data_vec_builder =
+                output_data_vec_builder.push(to_vec(&new_pausable_data, false)?.pack());
+            updated_cells_count += 1;
+        }
+
+        if updated_cells_count == 0 {
+            return Err(Error::NothingToDo);
+        }
+
+        approver_lock_hashes.sort();
+        approver_lock_hashes.dedup();
+        for approver_lock_hash in approver_lock_hashes {
+            input_vec_builder = input_vec_builder.push(
+                CellInput::new_builder()
+                    .previous_output(
+                        OutPoint::new_builder()
+                            .tx_hash(approver_lock_hash.pack())
+                            .index(APPROVER_PLACEHOLDER_INDEX.pack())
+                            .build(),
+                    )
+                    .build(),
+            );
+        }
+
+        return Ok(tx_builder
+            .raw(
+                raw_tx_builder
+                    .version(
+                        tx.clone()
+                            .map(|t| t.raw().version())
+                            .unwrap_or_else(|| Uint32::default()),
+                    )
+                    .cell_deps(
+                        tx.clone()
+                            .map(|t| t.raw().cell_deps())
+                            .unwrap_or_else(|| CellDepVec::default()),
+                    )
+                    .header_deps(
+                        tx.clone()
+                            .map(|t| t.raw().header_deps())
+                            .unwrap_or_else(|| Byte32Vec::default()),
+                    )
+                    .inputs(input_vec_builder.build())
+                    .outputs(cell_output_vec_builder.build())
+                    .outputs_data(output_data_vec_builder.build())
+                    .build(),
+            )
+            .witnesses(
+                tx.clone()
+                    .map(|t| t.witnesses())
+                    .unwrap_or_else(|| BytesVec::default()),
+            )
+            .build());
+    }
+
+    /// Returns the restriction level of each lock hash, combined over the whole pause list chain. Entries of `pause_list` count as `RESTRICTION_FULL_FREEZE`, and entries expired at `current_epoch` are left out.
+    // #[ssri_method(level = "script")]
+    pub fn restriction_levels(
+        lock_hashes: &[[u8; 32]],
+        current_epoch: Option<u64>,
+    ) -> Result<Vec<u8>, Error> {
+        debug!("Entered restriction_levels");
+        debug!("lock_hashes: {:?}", lock_hashes);
+        Ok(registry::restriction_levels(
+            &Self::load_registry_chain()?,
+            lock_hashes,
+            current_epoch,
+        ))
+    }
+
+    /// Enumerates the entries of the whole pause list chain with their levels and expiries, including expired ones. A `limit` of 0 means no limit.
+    // #[ssri_method(level = "script")]
+    pub fn enumerate_pause_entries(offset: u64, limit: u64) -> Result<PauseEntries, Error> {
+        debug!("Entered enumerate_pause_entries");
+        let registry_chain = Self::load_registry_chain()?;
+        let entries = registry_chain
+            .iter()
+            .flat_map(|pausable_data| pausable_data.entries())
+            .skip(offset as usize);
+        Ok(PauseEntries {
+            entries: match limit {
+                0 => entries.collect(),
+                _ => entries.take(limit as usize).collect(),
+            },
+        })
+    }
+
+    /// Removes the entries expired at `current_epoch` from every pause list cell of the chain.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn compact_expired_pauses(
+        tx: Option<Transaction>,
+        current_epoch: u64,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered compact_expired_pauses");
+        Self::build_registry_update(tx, |pausable_data, _| {
+            Ok(pausable_data.remove_expired(current_epoch))
+        })
+    }
+
+    /// Pauses `lock_hashes` at the given restriction level in the last pause list cell of the chain, until `expiry_epoch` if any. `RESTRICTION_FULL_FREEZE` without expiry is the same as `UDTPausable::pause`.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn pause_with_level(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+        level: u8,
+        expiry_epoch: Option<u64>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered pause_with_level");
+        if level == RESTRICTION_NONE
+            || level & !RESTRICTION_FULL_FREEZE != 0
+            || expiry_epoch == Some(0)
+        {
+            return Err(Error::SSRIMethodsArgsInvalid);
+        }
+        let mut deduped_lock_hashes = lock_hashes.to_vec();
+        deduped_lock_hashes.sort();
+        deduped_lock_hashes.dedup();
+
+        // Dedup lock_hashes against the restrictions already in place. Entries that expire may be renewed.
+        let registry_chain = Self::load_registry_chain()?;
+        let existing_levels =
+            registry::restriction_levels(&registry_chain, &deduped_lock_hashes, None);
+        deduped_lock_hashes = deduped_lock_hashes
+            .into_iter()
+            .zip(existing_levels)
+            .filter(|(lock_hash, existing_level)| {
+                existing_level & level != level
+                    || expiry_epoch.is_some()
+                    || registry_chain
+                        .iter()
+                        .any(|pausable_data| pausable_data.expiry_epoch(lock_hash).is_some())
+            })
+            .map(|(lock_hash, _)| lock_hash)
+            .collect();
+        if deduped_lock_hashes.len() == 0 {
+            return Err(Error::NothingToDo);
+        }
+
+        Self::build_registry_update(tx, |pausable_data, is_last| {
+            if !is_last {
+                return Ok(false);
+            }
+            for lock_hash in deduped_lock_hashes.iter() {
+                pausable_data.add_restriction(*lock_hash, level);
+                pausable_data.set_expiry(*lock_hash, expiry_epoch);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Returns whether a global pause is set in the pause list chain and active at `current_epoch`. Without `current_epoch`, any global pause is reported.
+    // #[ssri_method(level = "script")]
+    pub fn is_globally_paused(current_epoch: Option<u64>) -> Result<bool, Error> {
+        debug!("Entered is_globally_paused");
+        Ok(registry::global_pause(&Self::load_registry_chain()?)
+            .map(|global_pause| global_pause.is_active(current_epoch))
+            .unwrap_or(false))
+    }
+
+    /// Sets (`Some`) or lifts (`None`) the global pause. It is kept in the last pause list cell of the chain and cleared from any other.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn set_global_pause(
+        tx: Option<Transaction>,
+        global_pause: Option<GlobalPause>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered set_global_pause");
+        Self::build_registry_update(tx, |pausable_data, is_last| {
+            let new_global_pause = match is_last {
+                true => global_pause.clone(),
+                false => None,
+            };
+            if pausable_data.global_pause == new_global_pause {
+                return Ok(false);
+            }
+            pausable_data.global_pause = new_global_pause;
+            Ok(true)
+        })
+    }
+
+    /// Returns whether each lock is paused, either by its lock hash or by a lock code hash rule.
+    // #[ssri_method(level = "script")]
+    pub fn is_lock_paused(locks: &[Script]) -> Result<Vec<bool>, Error> {
+        debug!("Entered is_lock_paused");
+        let registry_chain = Self::load_registry_chain()?;
+        let lock_hashes: Vec<[u8; 32]> = locks
+            .iter()
+            .map(|lock| lock.calc_script_hash().unpack())
+            .collect();
+        Ok(
+            registry::restriction_levels(&registry_chain, &lock_hashes, None)
+                .into_iter()
+                .zip(registry::lock_code_hash_paused(&registry_chain, locks))
+                .map(|(level, rule_paused)| level != RESTRICTION_NONE || rule_paused)
+                .collect(),
+        )
+    }
+
+    /// Enumerates the lock code hash rules of the whole pause list chain. A `limit` of 0 means no limit.
+    // #[ssri_method(level = "script")]
+    pub fn enumerate_lock_code_hash_rules(
+        offset: u64,
+        limit: u64,
+    ) -> Result<LockCodeHashRules, Error> {
+        debug!("Entered enumerate_lock_code_hash_rules");
+        let rules = registry::lock_code_hash_rules(&Self::load_registry_chain()?)
+            .into_iter()
+            .skip(offset as usize);
+        Ok(LockCodeHashRules {
+            rules: match limit {
+                0 => rules.collect(),
+                _ => rules.take(limit as usize).collect(),
+            },
+        })
+    }
+
+    /// Pauses every lock with one of the given code hash and hash type pairs, in the last pause list cell of the chain.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn pause_lock_code_hashes(
+        tx: Option<Transaction>,
+        rules: &[LockCodeHashRule],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered pause_lock_code_hashes");
+        let existing_rules = registry::lock_code_hash_rules(&Self::load_registry_chain()?);
+        let new_rules: Vec<LockCodeHashRule> = rules
+            .iter()
+            .filter(|rule| !existing_rules.contains(rule))
+            .cloned()
+            .collect();
+        if new_rules.is_empty() {
+            return Err(Error::NothingToDo);
+        }
+        Self::build_registry_update(tx, |pausable_data, is_last| {
+            if !is_last {
+                return Ok(false);
+            }
+            for rule in new_rules.iter() {
+                pausable_data.add_lock_code_hash_rule(rule.clone());
+            }
+            Ok(true)
+        })
+    }
+
+    /// Lifts the given lock code hash rules from every pause list cell of the chain that has them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn unpause_lock_code_hashes(
+        tx: Option<Transaction>,
+        rules: &[LockCodeHashRule],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered unpause_lock_code_hashes");
+        Self::build_registry_update(tx, |pausable_data, _| {
+            Ok(pausable_data.remove_lock_code_hash_rules(rules))
+        })
+    }
+
+    /// Marks the entries of `lock_hashes` as pending unpause in every pause list cell of the chain that has them. They stay paused until `finalize_unpause`.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn schedule_unpause(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered schedule_unpause");
+        Self::build_registry_update(tx, |pausable_data, _| {
+            let mut is_updated = false;
+            for lock_hash in lock_hashes.iter() {
+                is_updated |= pausable_data.schedule_unpause(*lock_hash);
+            }
+            Ok(is_updated)
+        })
+    }
+
+    /// Lifts the entries of `lock_hashes` that are pending unpause. With `UNPAUSE_DELAY_EPOCHS`, each consumed pause list cell gets an epoch `since` so the transaction can only be committed once the delay has passed: an absolute one from the latest recorded scheduled epoch when every entry it lifts has one, or else a relative one of the delay, which requires the cell to have existed that long.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn finalize_unpause(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered finalize_unpause");
+        let registry_sinces: Vec<(OutPoint, u64)> = Self::find_registry_cells()?
+            .into_iter()
+            .map(|(out_point, pausable_data)| {
+                let scheduled_epochs: Vec<u64> = lock_hashes
+                    .iter()
+                    .filter_map(|lock_hash| pausable_data.pending_unpause(lock_hash))
+                    .map(|pending_unpause| pending_unpause.scheduled_epoch)
+                    .collect();
+                let since = match scheduled_epochs.iter().all(|&epoch| epoch != 0) {
+                    true => Since::from_epoch(
+                        EpochNumberWithFraction::new(
+                            scheduled_epochs.iter().max().copied().unwrap_or_default()
+                                + UNPAUSE_DELAY_EPOCHS,
+                            0,
+                            1,
+                        ),
+                        true,
+                    ),
+                    false => Since::from_epoch(
+                        EpochNumberWithFraction::new(UNPAUSE_DELAY_EPOCHS, 0, 1),
+                        false,
+                    ),
+                };
+                (out_point, since.as_u64())
+            })
+            .collect();
+        let tx = Self::build_registry_update(tx, |pausable_data, _| {
+            Ok(pausable_data.finalize_unpauses(lock_hashes))
+        })?;
+        if UNPAUSE_DELAY_EPOCHS == 0 {
+            return Ok(tx);
+        }
+
+        let mut input_vec_builder = CellInputVecBuilder::default();
+        for input in tx.raw().inputs().into_iter() {
+            let registry_since = registry_sinces
+                .iter()
+                .find(|(out_point, _)| out_point.as_slice() == input.previous_output().as_slice());
+            input_vec_builder = input_vec_builder.push(match registry_since {
+                Some((_, since)) => input.as_builder().since(since.pack()).build(),
+                None => input,
+            });
+        }
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(input_vec_builder.build())
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Sets the approvers and approval threshold of every pause list cell of the chain. A threshold of 0 goes back to the owner lock or roles. Once a threshold is set, this and every later pause list builder add approver placeholder inputs that the caller must replace with live cells of the approvers.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn set_approvers(
+        tx: Option<Transaction>,
+        approvers: &[[u8; 32]],
+        approval_threshold: u8,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered set_approvers");
+        let mut sorted_approvers = approvers.to_vec();
+        sorted_approvers.sort();
+        sorted_approvers.dedup();
+        if approval_threshold as usize > sorted_approvers.len() {
+            return Err(Error::InvalidApprovers);
+        }
+        Self::build_registry_update(tx, |pausable_data, _| {
+            if pausable_data.approvers == sorted_approvers
+                && pausable_data.approval_threshold == approval_threshold
+            {
+                return Ok(false);
+            }
+            pausable_data.approvers = sorted_approvers.clone();
+            pausable_data.approval_threshold = approval_threshold;
+            Ok(true)
+        })
+    }
+
+    /// Opens a proposal to pause `pause_list_additions` and unpause `pause_list_removals` in a single pause list cell: the one holding every lock hash to unpause, or else the last one of the chain. The proposal cell gets the lock of the first input of `tx` and the Type ID of that input.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn propose_pause_update(
+        tx: Option<Transaction>,
+        pause_list_additions: &[[u8; 32]],
+        pause_list_removals: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered propose_pause_update");
+        let tx = tx.ok_or(Error::NoPausePermission)?;
+        let mut pause_list_additions = pause_list_additions.to_vec();
+        pause_list_additions.sort();
+        pause_list_additions.dedup();
+        let mut pause_list_removals = pause_list_removals.to_vec();
+        pause_list_removals.sort();
+        pause_list_removals.dedup();
+        if (pause_list_additions.is_empty() && pause_list_removals.is_empty())
+            || pause_list_removals
+                .iter()
+                .any(|lock_hash| pause_list_additions.contains(lock_hash))
+        {
+            return Err(Error::InvalidProposalData);
+        }
+
+        let registry_cells = Self::find_registry_cells()?;
+        let (target_out_point, _) = match pause_list_removals.is_empty() {
+            true => registry_cells.last(),
+            false => registry_cells.iter().find(|(_, pausable_data)| {
+                pause_list_removals
+                    .iter()
+                    .all(|lock_hash| pausable_data.restriction_level(lock_hash) != RESTRICTION_NONE)
+            }),
+        }
+        .ok_or(Error::NothingToDo)?;
+        let proposal_data = UDTPauseProposalData {
+            registry_type_hash: find_cell_by_out_point(target_out_point.clone())?
+                .type_()
+                .to_opt()
+                .ok_or(Error::InvalidPauseData)?
+                .calc_script_hash()
+                .unpack(),
+            pause_list_additions,
+            pause_list_removals,
+            approvals: Vec::new(),
+        };
+
+        let first_input_cell_input = tx.raw().inputs().get(0).ok_or(Error::NoPausePermission)?;
+        let first_input_cell = find_cell_by_out_point(first_input_cell_input.previous_output())?;
+        let type_id = calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+        let proposal_output = CellOutput::new_builder()
+            .lock(first_input_cell.lock())
+            .type_(
+                ScriptOptBuilder::default()
+                    .set(Some(proposal::build_proposal_script(
+                        &load_script()?,
+                        type_id,
+                    )?))
+                    .build(),
+            )
+            .build();
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .outputs(
+                        tx.raw()
+                            .outputs()
+                            .as_builder()
+                            .push(proposal_output)
+                            .build(),
+                    )
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&proposal_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Adds the approval of `approver_lock_hash` to the proposal at `proposal_out_point`, along with a placeholder input for a cell of that lock. The caller must replace that input with a live cell of the approver, otherwise the transaction is invalid.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn approve_proposal(
+        tx: Option<Transaction>,
+        proposal_out_point: OutPoint,
+        approver_lock_hash: [u8; 32],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered approve_proposal");
+        let tx = tx.unwrap_or_default();
+        let mut proposal_data: UDTPauseProposalData = from_slice(
+            &find_cell_data_by_out_point(proposal_out_point.clone())?,
+            false,
+        )?;
+        if !proposal_data.approve(approver_lock_hash) {
+            return Err(Error::NothingToDo);
+        }
+        let proposal_output = find_cell_by_out_point(proposal_out_point.clone())?
+            .as_builder()
+            .capacity(Uint64::default())
+            .build();
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(
+                        tx.raw()
+                            .inputs()
+                            .as_builder()
+                            .push(
+                                CellInput::new_builder()
+                                    .previous_output(proposal_out_point)
+                                    .build(),
+                            )
+                            .push(
+                                CellInput::new_builder()
+                                    .previous_output(
+                                        OutPoint::new_builder()
+                                            .tx_hash(approver_lock_hash.pack())
+                                            .index(APPROVER_PLACEHOLDER_INDEX.pack())
+                                            .build(),
+                                    )
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .outputs(
+                        tx.raw()
+                            .outputs()
+                            .as_builder()
+                            .push(proposal_output)
+                            .build(),
+                    )
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&proposal_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Applies the proposal at `proposal_out_point` to the pause list cell it targets, consuming the proposal cell. The proposal must have reached the approval threshold of that cell.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn execute_proposal(
+        tx: Option<Transaction>,
+        proposal_out_point: OutPoint,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered execute_proposal");
+        let tx = tx.unwrap_or_default();
+        let proposal_data: UDTPauseProposalData = from_slice(
+            &find_cell_data_by_out_point(proposal_out_point.clone())?,
+            false,
+        )?;
+        let mut target_registry_cell: Option<(OutPoint, CellOutput, PausableRegistryData)> = None;
+        for (out_point, pausable_data) in Self::find_registry_cells()? {
+            let cell_output = find_cell_by_out_point(out_point.clone())?;
+            let is_target = cell_output
+                .type_()
+                .to_opt()
+                .map(|type_script| {
+                    type_script.calc_script_hash().as_slice() == proposal_data.registry_type_hash
+                })
+                .unwrap_or(false);
+            if is_target {
+                target_registry_cell = Some((out_point, cell_output, pausable_data));
+                break;
+            }
+        }
+        let (registry_out_point, registry_cell_output, pausable_data) =
+            target_registry_cell.ok_or(Error::InvalidProposalData)?;
+        if proposal_data.count_approvals(&pausable_data.approvers)
+            < pausable_data.approval_threshold as usize
+            || pausable_data.approval_threshold == 0
+        {
+            return Err(Error::ProposalNotApproved);
+        }
+
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(
+                        tx.raw()
+                            .inputs()
+                            .as_builder()
+                            .push(
+                                CellInput::new_builder()
+                                    .previous_output(proposal_out_point)
+                                    .build(),
+                            )
+                            .push(
+                                CellInput::new_builder()
+                                    .previous_output(registry_out_point)
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .outputs(
+                        tx.raw()
+                            .outputs()
+                            .as_builder()
+                            .push(
+                                registry_cell_output
+                                    .as_builder()
+                                    .capacity(Uint64::default())
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&proposal_data.apply(&pausable_data), false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build())
+    }
+
+    /// Sets the guardian lock hash of every pause list cell of the chain, or removes it with `None`. This lifts the guardian's power to pause, so it needs the same permissions as unpausing.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn set_guardian(
+        tx: Option<Transaction>,
+        guardian_lock_hash: Option<[u8; 32]>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered set_guardian");
+        Self::build_registry_update(tx, |pausable_data, _| {
+            if pausable_data.guardian_lock_hash == guardian_lock_hash {
+                return Ok(false);
+            }
+            pausable_data.guardian_lock_hash = guardian_lock_hash;
+            Ok(true)
+        })
+    }
+
+    /// Returns whether each type script hash is blocked by any pause list cell of the chain.
+    // #[ssri_method(level = "script")]
+    pub fn is_type_blocked(type_hashes: &[[u8; 32]]) -> Result<Vec<bool>, Error> {
+        debug!("Entered is_type_blocked");
+        Ok(registry::blocked_type_hashes(
+            &Self::load_registry_chain()?,
+            type_hashes,
+        ))
+    }
+
+    /// Blocks the given type script hashes in the last pause list cell of the chain, so that no transaction of the token may carry a cell with one of them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn block_type_hashes(
+        tx: Option<Transaction>,
+        type_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered block_type_hashes");
+        let already_blocked =
+            registry::blocked_type_hashes(&Self::load_registry_chain()?, type_hashes);
+        let new_type_hashes: Vec<[u8; 32]> = type_hashes
+            .iter()
+            .zip(already_blocked)
+            .filter(|(_, blocked)| !blocked)
+            .map(|(type_hash, _)| *type_hash)
+            .collect();
+        if new_type_hashes.is_empty() {
+            return Err(Error::NothingToDo);
+        }
+        Self::build_registry_update(tx, |pausable_data, is_last| {
+            if !is_last {
+                return Ok(false);
+            }
+            for type_hash in new_type_hashes.iter() {
+                pausable_data.block_type_hash(*type_hash);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Unblocks the given type script hashes in every pause list cell of the chain that blocks them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn unblock_type_hashes(
+        tx: Option<Transaction>,
+        type_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered unblock_type_hashes");
+        Self::build_registry_update(tx, |pausable_data, _| {
+            Ok(pausable_data.unblock_type_hashes(type_hashes))
+        })
+    }
+
+    /// Returns whether each cell is frozen by any pause list cell of the chain.
+    // #[ssri_method(level = "script")]
+    pub fn is_cell_frozen(out_points: &[OutPoint]) -> Result<Vec<bool>, Error> {
+        debug!("Entered is_cell_frozen");
+        let frozen_cells: Vec<FrozenCell> = out_points.iter().map(FrozenCell::from).collect();
+        Ok(registry::frozen_cells(
+            &Self::load_registry_chain()?,
+            &frozen_cells,
+        ))
+    }
+
+    /// Freezes the given token cells in the last pause list cell of the chain, so that they can not be spent whatever their lock is.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn freeze_cells(
+        tx: Option<Transaction>,
+        out_points: &[OutPoint],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered freeze_cells");
+        let script = load_script()?;
+        let mut frozen_cells: Vec<FrozenCell> = Vec::new();
+        for out_point in out_points.iter() {
+            if find_cell_by_out_point(out_point.clone())?.type_().to_opt() != Some(script.clone()) {
+                return Err(Error::SSRIMethodsArgsInvalid);
+            }
+            frozen_cells.push(out_point.into());
+        }
+
+        // Dedup against the cells already frozen
+        let already_frozen = registry::frozen_cells(&Self::load_registry_chain()?, &frozen_cells);
+        let frozen_cells: Vec<FrozenCell> = frozen_cells
+            .into_iter()
+            .zip(already_frozen)
+            .filter(|(_, frozen)| !frozen)
+            .map(|(frozen_cell, _)| frozen_cell)
+            .collect();
+        if frozen_cells.is_empty() {
+            return Err(Error::NothingToDo);
+        }
+
+        Self::build_registry_update(tx, |pausable_data, is_last| {
+            if !is_last {
+                return Ok(false);
+            }
+            for frozen_cell in frozen_cells.iter() {
+                pausable_data.freeze_cell(frozen_cell.clone());
+            }
+            Ok(true)
+        })
+    }
+
+    /// Unfreezes the given cells in every pause list cell of the chain that freezes them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn unfreeze_cells(
+        tx: Option<Transaction>,
+        out_points: &[OutPoint],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered unfreeze_cells");
+        let frozen_cells: Vec<FrozenCell> = out_points.iter().map(FrozenCell::from).collect();
+        Self::build_registry_update(tx, |pausable_data, _| {
+            Ok(pausable_data.unfreeze_cells(&frozen_cells))
+        })
+    }
+
+    /// Builds a clawback moving the given paused or frozen token cells to the recovery lock as a single output. An input in owner mode still has to be added to unlock it.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn clawback(
+        tx: Option<Transaction>,
+        clawback_out_points: Vec<OutPoint>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDTPausable::clawback");
+        let recovery_lock = get_recovery_lock()?.ok_or(Error::NoClawbackPermission)?;
+        let registry_chain = Self::load_registry_chain()?;
+        for clawback_out_point in clawback_out_points.iter() {
+            let clawback_cell = find_cell_by_out_point(clawback_out_point.clone())?;
+            // Only funds that can not move otherwise may be clawed back
+            let lock_hash: [u8; 32] = clawback_cell.lock().calc_script_hash().unpack();
+            let is_paused = registry::restriction_levels(&registry_chain, &[lock_hash], None)[0]
+                & RESTRICTION_BLOCK_SEND
+                != 0;
+            let is_frozen =
+                registry::frozen_cells(&registry_chain, &[clawback_out_point.into()])[0];
+            if !is_paused && !is_frozen {
+                return Err(Error::NoClawbackPermission);
+            }
+        }
+        Self::build_merged_transfer(tx, &clawback_out_points, recovery_lock)
+    }
+
+    /// Builds a redemption moving the given token cells, paused or not, to `redemption_lock` as a single output. `redemption_lock` must be a redemption lock of the pause list chain, and the locks of the cells still have to unlock them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn redeem(
+        tx: Option<Transaction>,
+        redemption_lock: Script,
+        redeem_out_points: Vec<OutPoint>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDTPausable::redeem");
+        let redemption_lock_hash: [u8; 32] = redemption_lock.calc_script_hash().unpack();
+        if !registry::redemption_lock_hashes(&Self::load_registry_chain()?, &[redemption_lock_hash])
+            [0]
+        {
+            return Err(Error::NotRedemptionLock);
+        }
+        Self::build_merged_transfer(tx, &redeem_out_points, redemption_lock)
+    }
+
+    /// Returns whether each lock hash is a redemption lock in any pause list cell of the chain.
+    // #[ssri_method(level = "script")]
+    pub fn is_redemption_lock(lock_hashes: &[[u8; 32]]) -> Result<Vec<bool>, Error> {
+        debug!("Entered is_redemption_lock");
+        Ok(registry::redemption_lock_hashes(
+            &Self::load_registry_chain()?,
+            lock_hashes,
+        ))
+    }
+
+    /// Adds the given lock hashes as redemption locks in the last pause list cell of the chain. Paused holders may then send their funds to them, so this needs the same permissions as unpausing.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn add_redemption_locks(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered add_redemption_locks");
+        let already_added =
+            registry::redemption_lock_hashes(&Self::load_registry_chain()?, lock_hashes);
+        let new_lock_hashes: Vec<[u8; 32]> = lock_hashes
+            .iter()
+            .zip(already_added)
+            .filter(|(_, added)| !added)
+            .map(|(lock_hash, _)| *lock_hash)
+            .collect();
+        if new_lock_hashes.is_empty() {
+            return Err(Error::NothingToDo);
+        }
+        Self::build_registry_update(tx, |pausable_data, is_last| {
+            if !is_last {
+                return Ok(false);
+            }
+            for lock_hash in new_lock_hashes.iter() {
+                pausable_data.add_redemption_lock_hash(*lock_hash);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Removes the given redemption locks from every pause list cell of the chain that lists them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn remove_redemption_locks(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered remove_redemption_locks");
+        Self::build_registry_update(tx, |pausable_data, _| {
+            Ok(pausable_data.remove_redemption_lock_hashes(lock_hashes))
+        })
+    }
+
+    /// Spends the given token cells into a single output of `lock`, with the pause list chain in `CellDep`.
+    fn build_merged_transfer(
+        tx: Option<Transaction>,
+        out_points: &[OutPoint],
+        lock: Script,
+    ) -> Result<Transaction, Error> {
+        if out_points.is_empty() {
+            return Err(Error::SSRIMethodsArgsInvalid);
+        }
+        let script = load_script()?;
+        let tx_builder = match tx {
+            Some(ref tx) => tx.clone().as_builder(),
+            None => TransactionBuilder::default(),
+        };
+        let raw_tx_builder = match tx {
+            Some(ref tx) => tx.clone().raw().as_builder(),
+            None => RawTransactionBuilder::default(),
+        };
+
+        let mut input_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().inputs().as_builder(),
+            None => CellInputVecBuilder::default(),
+        };
+        let mut amount: u128 = 0;
+        let mut merged_extension_data: Option<Vec<u8>> = None;
+        for out_point in out_points.iter() {
+            let cell = find_cell_by_out_point(out_point.clone())?;
+            if cell.type_().to_opt().as_ref() != Some(&script) {
+                return Err(Error::SSRIMethodsArgsInvalid);
+            }
+            let cell_data = find_cell_data_by_out_point(out_point.clone())?;
+            let (cell_amount, extension_data) = parse_amount(&cell_data)?;
+            merged_extension_data.get_or_insert_with(|| extension_data.to_vec());
+            amount = amount.checked_add(cell_amount).ok_or(Error::Overflow)?;
+            input_vec_builder = input_vec_builder.push(
+                CellInput::new_builder()
+                    .previous_output(out_point.clone())
+                    .build(),
+            );
+        }
+
+        let cell_output_vec_builder = match tx {
+            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
+            None => CellOutputVecBuilder::default(),
+        }
+        .push(
+            CellOutputBuilder::default()
+                .type_(ScriptOptBuilder::default().set(Some(script)).build())
+                .capacity(Uint64::default())
+                .lock(lock)
+                .build(),
+        );
+        let outputs_data_builder = match tx {
+            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
+            None => BytesVecBuilder::default(),
+        }
+        .push(encode_amount(amount, &merged_extension_data.unwrap_or_default()).pack());
+
+        let cell_dep_vec = Self::build_registry_cell_deps(&tx)?;
+
+        Ok(tx_builder
+            .raw(
+                raw_tx_builder
+                    .version(tx.clone().map(|t| t.raw().version()).unwrap_or_default())
+                    .cell_deps(cell_dep_vec)
+                    .header_deps(
+                        tx.clone()
+                            .map(|t| t.raw().header_deps())
+                            .unwrap_or_default(),
+                    )
+                    .inputs(input_vec_builder.build())
+                    .outputs(cell_output_vec_builder.build())
+                    .outputs_data(outputs_data_builder.build())
+                    .build(),
+            )
+            .witnesses(tx.clone().map(|t| t.witnesses()).unwrap_or_default())
+            .build())
+    }
+
+    /// Returns whether each lock hash may hold the token. Every lock hash is allowed when the allowlist is not configured.
+    // #[ssri_method(level = "script")]
+    pub fn is_allowed(lock_hashes: &[[u8; 32]]) -> Result<Vec<bool>, Error> {
+        debug!("Entered is_allowed");
+        let allowlist_chain = allowlist::load_allowlist_chain()?;
+        Ok(lock_hashes
+            .iter()
+            .map(|lock_hash| match allowlist_chain {
+                Some(ref chain) => allowlist::is_allowed(chain, lock_hash),
+                None => true,
+            })
+            .collect())
+    }
+
+    /// Enumerates the lock hashes of the whole allowlist chain. A `limit` of 0 means no limit.
+    // #[ssri_method(level = "script")]
+    pub fn enumerate_allowed(offset: u64, limit: u64) -> Result<Byte32Vec, Error> {
+        debug!("Entered enumerate_allowed");
+        let allowlist_chain = allowlist::load_allowlist_chain()?.unwrap_or_default();
+        let allowed = allowlist_chain
+            .iter()
+            .flat_map(|allowlist_data| allowlist_data.allow_list.iter())
+            .skip(offset as usize);
+        let mut allowed_byte32_vec_builder = Byte32Vec::new_builder();
+        for lock_hash in allowed.take(match limit {
+            0 => usize::MAX,
+            _ => limit as usize,
+        }) {
+            allowed_byte32_vec_builder = allowed_byte32_vec_builder.push(lock_hash.pack());
+        }
+        Ok(allowed_byte32_vec_builder.build())
+    }
+
+    /// Allows `lock_hashes` in the last allowlist cell of the chain. Without a configured allowlist, the first allowlist cell is created from the first input of `tx`.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn add_to_allowlist(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered add_to_allowlist");
+        let allowlist_chain = allowlist::load_allowlist_chain()?.unwrap_or_default();
+        let mut new_lock_hashes: Vec<[u8; 32]> = lock_hashes
+            .iter()
+            .filter(|lock_hash| !allowlist::is_allowed(&allowlist_chain, lock_hash))
+            .cloned()
+            .collect();
+        new_lock_hashes.sort();
+        new_lock_hashes.dedup();
+        if new_lock_hashes.is_empty() {
+            return Err(Error::NothingToDo);
+        }
+        Self::build_allowlist_update(tx, |allowlist_data, is_last| {
+            if !is_last {
+                return Ok(false);
+            }
+            for lock_hash in new_lock_hashes.iter() {
+                allowlist_data.allow(*lock_hash);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Removes `lock_hashes` from every allowlist cell of the chain that allows them.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn remove_from_allowlist(
+        tx: Option<Transaction>,
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered remove_from_allowlist");
+        if allowlist::get_allowlist_script()?.is_none() {
+            return Err(Error::NothingToDo);
+        }
+        Self::build_allowlist_update(tx, |allowlist_data, _| {
+            Ok(allowlist_data.disallow(lock_hashes))
+        })
+    }
+
+    /// Finds the out point and data of every allowlist cell of the chain through SSRI.
+    fn find_allowlist_cells() -> Result<Vec<(OutPoint, UDTAllowlistData)>, Error> {
+        let mut allowlist_cells: Vec<(OutPoint, UDTAllowlistData)> = Vec::new();
+        let mut next_type_script = allowlist::get_allowlist_script()?;
+        let mut seen_type_hashes: Vec<Byte32> = Vec::new();
+        while let Some(allowlist_script) = next_type_script {
+            if seen_type_hashes.contains(&allowlist_script.calc_script_hash()) {
+                return Err(Error::CyclicAllowlist);
+            }
+            seen_type_hashes.push(allowlist_script.calc_script_hash());
+
+            let out_point = find_out_point_by_type(allowlist_script)?;
+            let allowlist_data: UDTAllowlistData =
+                from_slice(&find_cell_data_by_out_point(out_point.clone())?, false)?;
+            next_type_script = allowlist_data
+                .next_type_script
+                .as_ref()
+                .map(script_like_to_script);
+            allowlist_cells.push((out_point, allowlist_data));
+        }
+        Ok(allowlist_cells)
+    }
+
+    /// Like `build_registry_update`, for allowlist cells.
+    fn build_allowlist_update<F>(
+        tx: Option<Transaction>,
+        mut update: F,
+    ) -> Result<Transaction, Error>
+    where
+        F: FnMut(&mut UDTAllowlistData, bool) -> Result<bool, Error>,
+    {
+        let tx = tx.ok_or(Error::NoPausePermission)?;
+        let mut input_vec_builder = tx.raw().inputs().as_builder();
+        let mut cell_output_vec_builder = tx.raw().outputs().as_builder();
+        let mut output_data_vec_builder = tx.raw().outputs_data().as_builder();
+
+        let mut updated_cells_count = 0;
+
+        let allowlist_cells = Self::find_allowlist_cells()?;
+        if allowlist_cells.is_empty() {
+            debug!("No allowlist cell configured. Try to generate the first allowlist cell.");
+            let mut new_allowlist_data = UDTAllowlistData::default();
+            if !update(&mut new_allowlist_data, true)? {
+                return Err(Error::NothingToDo);
+            }
+            let first_input_cell_input =
+                tx.raw().inputs().get(0).ok_or(Error::NoPausePermission)?;
+            let first_input_cell =
+                find_cell_by_out_point(first_input_cell_input.previous_output())?;
+            let type_id = calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+            cell_output_vec_builder = cell_output_vec_builder.push(
+                CellOutput::new_builder()
+                    .lock(first_input_cell.lock())
+                    .type_(
+                        ScriptOptBuilder::default()
+                            .set(Some(allowlist::build_allowlist_script(
+                                &load_script()?,
+                                type_id,
+                            )?))
+                            .build(),
+                    )
+                    .build(),
+            );
+            output_data_vec_builder =
+                output_data_vec_builder.push(to_vec(&new_allowlist_data, false)?.pack());
+            updated_cells_count += 1;
+        }
+
+        let last_index = allowlist_cells.len().saturating_sub(1);
+        for (index, (out_point, allowlist_data)) in allowlist_cells.into_iter().enumerate() {
+            let mut new_allowlist_data = allowlist_data;
+            if !update(&mut new_allowlist_data, index == last_index)? {
+                continue;
+            }
+            let new_cell_output = find_cell_by_out_point(out_point.clone())?
+                .as_builder()
+                .capacity(Uint64::default())
+                .build();
+            input_vec_builder =
+                input_vec_builder.push(CellInput::new_builder().previous_output(out_point).build());
+            cell_output_vec_builder = cell_output_vec_builder.push(new_cell_output);
+            output_data_vec_builder =
+                output_data_vec_builder.push(to_vec(&new_allowlist_data, false)?.pack());
+            updated_cells_count += 1;
+        }
+        if updated_cells_count == 0 {
+            return Err(Error::NothingToDo);
+        }
+
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(input_vec_builder.build())
+                    .outputs(cell_output_vec_builder.build())
+                    .outputs_data(output_data_vec_builder.build())
+                    .build(),
+            )
+            .build())
+    }
+
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn smt_pause(
+        tx: Option<Transaction>,
+        paused_lock_hashes: &[[u8; 32]],
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered smt_pause");
+        Self::update_smt_registry(tx, paused_lock_hashes, lock_hashes, true)
+    }
+
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn smt_unpause(
+        tx: Option<Transaction>,
+        paused_lock_hashes: &[[u8; 32]],
+        lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered smt_unpause");
+        Self::update_smt_registry(tx, paused_lock_hashes, lock_hashes, false)
+    }
+
+    /// `paused_lock_hashes` is the full leaf set of the SMT registry (e.g. rebuilt from the witnesses of past updates); it is checked against the on-chain root before any proof is generated.
+    fn update_smt_registry(
+        tx: Option<Transaction>,
+        paused_lock_hashes: &[[u8; 32]],
+        lock_hashes: &[[u8; 32]],
+        paused: bool,
+    ) -> Result<Transaction, Error> {
+        let tx = tx.ok_or(Error::NoPausePermission)?;
+        let mut leaves = paused_lock_hashes.to_vec();
+        leaves.sort();
+        leaves.dedup();
+
+        let new_cell_output: CellOutput;
+        let new_cell_input: Option<CellInput>;
+        match smt::get_smt_registry_script()? {
+            Some(smt_registry_script) => {
+                let smt_cell_out_point = find_out_point_by_type(smt_registry_script)?;
+                let smt_data: smt::UDTPausableSmtData = from_slice(
+                    &find_cell_data_by_out_point(smt_cell_out_point.clone())?,
+                    false,
+                )?;
+                if smt::compute_root_from_leaves(&leaves) != smt_data.root {
+                    return Err(Error::IncompletePauseList);
+                }
+                new_cell_output = find_cell_by_out_point(smt_cell_out_point.clone())?
+                    .as_builder()
+                    .capacity(Uint64::default())
+                    .build();
+                new_cell_input = Some(
+                    CellInput::new_builder()
+                        .previous_output(smt_cell_out_point)
+                        .build(),
+                );
+            }
+            None => {
+                debug!("No SMT registry cell configured. Try to generate the SMT registry cell.");
+                if !leaves.is_empty() {
+                    return Err(Error::IncompletePauseList);
+                }
+                let first_input_cell_input =
+                    tx.raw().inputs().get(0).ok_or(Error::NoPausePermission)?;
+                let first_input_cell =
+                    find_cell_by_out_point(first_input_cell_input.previous_output())?;
+                let type_id =
+                    calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+                new_cell_output = CellOutput::new_builder()
+                    .lock(first_input_cell.lock())
+                    .type_(
+                        ScriptOptBuilder::default()
+                            .set(Some(smt::build_smt_registry_script(
+                                &load_script()?,
+                                type_id,
+                            )?))
+                            .build(),
+                    )
+                    .build();
+                new_cell_input = None;
+            }
+        }
+
+        let mut sorted_lock_hashes = lock_hashes.to_vec();
+        sorted_lock_hashes.sort();
+        sorted_lock_hashes.dedup();
+        let mut smt_updates = smt::SmtUpdates::default();
+        for lock_hash in sorted_lock_hashes.iter() {
+            match leaves.binary_search(lock_hash) {
+                Ok(_) if paused => continue,
+                Err(_) if !paused => continue,
+                _ => {}
+            }
+            smt_updates.updates.push(smt::SmtUpdate {
+                lock_hash: *lock_hash,
+                paused: paused.into(),
+                proof: smt::generate_proof(&leaves, lock_hash),
+            });
+            match leaves.binary_search(lock_hash) {
+                Ok(index) => {
+                    leaves.remove(index);
+                }
+                Err(index) => leaves.insert(index, *lock_hash),
+            }
+        }
+        if smt_updates.updates.is_empty() {
+            return Err(Error::NothingToDo);
+        }
+
+        let new_output_data = smt::UDTPausableSmtData {
+            root: smt::compute_root_from_leaves(&leaves),
+        };
+        let (witness_index, witness_source) = match new_cell_input {
+            Some(_) => (tx.raw().inputs().len(), Source::Input),
+            None => (tx.raw().outputs().len(), Source::Output),
+        };
+        let mut input_vec_builder = tx.raw().inputs().as_builder();
+        if let Some(new_cell_input) = new_cell_input {
+            input_vec_builder = input_vec_builder.push(new_cell_input);
+        }
+        let witnesses = set_witness_args_type(
+            tx.witnesses(),
+            witness_index,
+            witness_source,
+            to_vec(&smt_updates, false)?,
+        )?;
+
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .inputs(input_vec_builder.build())
+                    .outputs(
+                        tx.raw()
+                            .outputs()
+                            .as_builder()
+                            .push(new_cell_output)
+                            .build(),
+                    )
+                    .outputs_data(
+                        tx.raw()
+                            .outputs_data()
+                            .as_builder()
+                            .push(to_vec(&new_output_data, false)?.pack())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .witnesses(witnesses)
+            .build())
+    }
+
+    /// Attaches SMT non-membership proofs for every lock hash in the transaction to the token witness. Inputs must already be filled in.
+    // #[ssri_method(level = "script", transaction = true)]
+    pub fn attach_smt_proofs(
+        tx: Transaction,
+        paused_lock_hashes: &[[u8; 32]],
+    ) -> Result<Transaction, Error> {
+        debug!("Entered attach_smt_proofs");
+        let smt_registry_script = smt::get_smt_registry_script()?.ok_or(Error::NothingToDo)?;
+        let smt_cell_out_point = find_out_point_by_type(smt_registry_script)?;
+        let smt_data: smt::UDTPausableSmtData = from_slice(
+            &find_cell_data_by_out_point(smt_cell_out_point.clone())?,
+            false,
+        )?;
+        let mut leaves = paused_lock_hashes.to_vec();
+        leaves.sort();
+        leaves.dedup();
+        if smt::compute_root_from_leaves(&leaves) != smt_data.root {
+            return Err(Error::IncompletePauseList);
+        }
+
+        let script = load_script()?;
+        let mut lock_hashes: Vec<[u8; 32]> = Vec::new();
+        let mut witness_position: Option<(usize, Source)> = None;
+        for (index, cell_input) in tx.raw().inputs().into_iter().enumerate() {
+            let input_cell = find_cell_by_out_point(cell_input.previous_output())?;
+            lock_hashes.push(input_cell.lock().calc_script_hash().unpack());
+            if witness_position.is_none() && input_cell.type_().to_opt() == Some(script.clone()) {
+                witness_position = Some((index, Source::Input));
+            }
+        }
+        for (index, output) in tx.raw().outputs().into_iter().enumerate() {
+            lock_hashes.push(output.lock().calc_script_hash().unpack());
+            if witness_position.is_none() && output.type_().to_opt() == Some(script.clone()) {
+                witness_position = Some((index, Source::Output));
+            }
+        }
+        let (witness_index, witness_source) = witness_position.ok_or(Error::NothingToDo)?;
+        lock_hashes.sort();
+        lock_hashes.dedup();
+
+        let mut smt_lock_proofs = smt::SmtLockProofs::default();
+        for lock_hash in lock_hashes.iter() {
+            if leaves.binary_search(lock_hash).is_ok() {
+                return Err(Error::AbortedFromPause);
+            }
+            smt_lock_proofs.proofs.push(smt::SmtLockProof {
+                lock_hash: *lock_hash,
+                proof: smt::generate_proof(&leaves, lock_hash),
+            });
+        }
+        let witnesses = set_witness_args_type(
+            tx.witnesses(),
+            witness_index,
+            witness_source,
+            to_vec(&smt_lock_proofs, false)?,
+        )?;
+
+        let smt_cell_dep = CellDep::new_builder().out_point(smt_cell_out_point).build();
+        let mut cell_dep_vec_builder = tx.raw().cell_deps().as_builder();
+        if !tx
+            .raw()
+            .cell_deps()
+            .into_iter()
+            .any(|cell_dep| cell_dep == smt_cell_dep)
+        {
+            cell_dep_vec_builder = cell_dep_vec_builder.push(smt_cell_dep);
+        }
+
+        Ok(tx
+            .clone()
+            .as_builder()
+            .raw(
+                tx.raw()
+                    .as_builder()
+                    .cell_deps(cell_dep_vec_builder.build())
+                    .build(),
+            )
+            .witnesses(witnesses)
+            .build())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::config::{OracleAttestationScope, COMPLIANCE_ORACLE_PUBKEY, ORACLE_ATTESTATION_SCOPE};
+use crate::error::Error;
+use alloc::ffi::CString;
+use ckb_hash::new_blake2b;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::prelude::*;
+use ckb_std::debug;
+use ckb_std::error::SysError;
+use ckb_std::high_level::{
+    decode_hex, load_input, load_script_hash, load_tx_hash, load_witness_args,
+};
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+
+// NOTE: The oracle signs a 32-byte message with a compact (r, s) secp256k1 signature, carried in the `output_type` of the token witness. The `input_type` of that witness is left to the SMT non-membership proofs.
+pub const ORACLE_SIGNATURE_LEN: usize = 64;
+
+/// Returns the public key of the compliance oracle, or `None` when screening is disabled.
+pub fn get_oracle_pubkey() -> Result<Option<VerifyingKey>, Error> {
+    if COMPLIANCE_ORACLE_PUBKEY.is_empty() {
+        return Ok(None);
+    }
+    let pubkey = decode_hex(
+        &CString::new(COMPLIANCE_ORACLE_PUBKEY)
+            .map_err(|_| Error::InvalidPauseData)?
+            .as_c_str()[2..],
+    )?;
+    Ok(Some(
+        VerifyingKey::from_sec1_bytes(&pubkey).map_err(|_| Error::InvalidPauseData)?,
+    ))
+}
+
+/// The message the oracle signs for a transfer: the transaction hash, or the hash of the token type script hash, the out point of the first input and `lock_hashes` (sorted and deduplicated), so that one screening covers the parties of a single transaction while its other details may still change.
+pub fn attestation_message(lock_hashes: &[[u8; 32]]) -> Result<[u8; 32], Error> {
+    match ORACLE_ATTESTATION_SCOPE {
+        OracleAttestationScope::TransactionHash => Ok(load_tx_hash()?),
+        // NOTE: The out point of the first input is a nonce: it can only be spent once, so the attestation can not be replayed. An expiry would not do, as a script can only prove that the chain is past some point in time, never that it is not.
+        OracleAttestationScope::LockHashes => {
+            let mut hasher = new_blake2b();
+            hasher.update(&load_script_hash()?);
+            hasher.update(load_input(0, Source::Input)?.previous_output().as_slice());
+            for lock_hash in lock_hashes.iter() {
+                hasher.update(lock_hash);
+            }
+            let mut message = [0u8; 32];
+            hasher.finalize(&mut message);
+            Ok(message)
+        }
+    }
+}
+
+fn load_oracle_signature() -> Result<Signature, Error> {
+    // Mints may have no token input, in which case the witness at the index of the first token output is used
+    let witness_args = match load_witness_args(0, Source::GroupInput) {
+        Err(SysError::IndexOutOfBound) => load_witness_args(0, Source::GroupOutput),
+        witness_args => witness_args,
+    };
+    let signature_bytes = match witness_args {
+        Ok(witness_args) => witness_args.output_type().to_opt(),
+        Err(SysError::IndexOutOfBound) => None,
+        Err(err) => return Err(err.into()),
+    }
+    .ok_or(Error::MissingOracleAttestation)?;
+    if signature_bytes.raw_data().len() != ORACLE_SIGNATURE_LEN {
+        return Err(Error::InvalidOracleAttestation);
+    }
+    Signature::from_slice(&signature_bytes.raw_data()).map_err(|_| Error::InvalidOracleAttestation)
+}
+
+/// Requires a signature of the compliance oracle over the attestation message when screening is enabled.
+pub fn verify_oracle_attestation(lock_hashes: &[[u8; 32]]) -> Result<(), Error> {
+    debug!("Entered verify_oracle_attestation");
+    let oracle_pubkey = match get_oracle_pubkey()? {
+        Some(pubkey) => pubkey,
+        None => return Ok(()),
+    };
+    let signature = load_oracle_signature()?;
+    let message = attestation_message(lock_hashes)?;
+    oracle_pubkey
+        .verify_prehash(&message, &signature)
+        .map_err(|_| {
+            debug!("Oracle attestation does not match message: {:?}", message);
+            Error::InvalidOracleAttestation
+        })
+}
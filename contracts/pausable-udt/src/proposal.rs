@@ -0,0 +1,173 @@
+use crate::error::Error;
+use crate::registry::{self, PausableRegistryData, PROPOSAL_KIND, RESTRICTION_FULL_FREEZE};
+use crate::utils::check_owner_mode;
+use alloc::vec::Vec;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::Script;
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::high_level::{
+    load_cell_data, load_cell_lock_hash, load_cell_type, load_cell_type_hash, load_script,
+    QueryIter,
+};
+use serde_molecule::{from_slice, to_vec};
+
+pub use crate::cell_data::UDTPauseProposalData;
+
+impl UDTPauseProposalData {
+    /// Returns the data of the targeted pause list cell once the proposed change is applied.
+    pub fn apply(&self, pausable_data: &PausableRegistryData) -> PausableRegistryData {
+        let mut new_pausable_data = pausable_data.clone();
+        for lock_hash in self.pause_list_additions.iter() {
+            new_pausable_data.add_restriction(*lock_hash, RESTRICTION_FULL_FREEZE);
+        }
+        new_pausable_data.remove_restrictions(&self.pause_list_removals);
+        new_pausable_data
+    }
+
+    /// Counts the approvals given by members of `approvers`, which must be sorted.
+    pub fn count_approvals(&self, approvers: &[[u8; 32]]) -> usize {
+        self.approvals
+            .iter()
+            .filter(|lock_hash| approvers.binary_search(lock_hash).is_ok())
+            .count()
+    }
+
+    /// Adds `lock_hash` to `approvals`, which stays sorted. Returns whether it had not approved yet.
+    pub fn approve(&mut self, lock_hash: [u8; 32]) -> bool {
+        match self.approvals.binary_search(&lock_hash) {
+            Ok(_) => false,
+            Err(index) => {
+                self.approvals.insert(index, lock_hash);
+                true
+            }
+        }
+    }
+
+    fn is_same_change(&self, other: &UDTPauseProposalData) -> bool {
+        self.registry_type_hash == other.registry_type_hash
+            && self.pause_list_additions == other.pause_list_additions
+            && self.pause_list_removals == other.pause_list_removals
+    }
+}
+
+pub fn build_proposal_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let registry_script = registry::build_registry_script(script, type_id)?;
+    let mut args: Vec<u8> = registry_script.args().unpack();
+    args.push(PROPOSAL_KIND);
+    Ok(registry_script.as_builder().args(args.pack()).build())
+}
+
+fn load_canonical_proposal_data(
+    index: usize,
+    source: Source,
+) -> Result<UDTPauseProposalData, Error> {
+    let data = load_cell_data(index, source)?;
+    let proposal_data: UDTPauseProposalData =
+        from_slice(&data, false).map_err(|_| Error::InvalidProposalData)?;
+    if to_vec(&proposal_data, false)? != data {
+        return Err(Error::InvalidProposalData);
+    }
+    let is_sorted = |lock_hashes: &[[u8; 32]]| lock_hashes.windows(2).all(|pair| pair[0] < pair[1]);
+    if !is_sorted(&proposal_data.pause_list_additions)
+        || !is_sorted(&proposal_data.pause_list_removals)
+        || !is_sorted(&proposal_data.approvals)
+    {
+        return Err(Error::InvalidProposalData);
+    }
+    if proposal_data.pause_list_additions.is_empty() && proposal_data.pause_list_removals.is_empty()
+    {
+        return Err(Error::InvalidProposalData);
+    }
+    if proposal_data
+        .pause_list_removals
+        .iter()
+        .any(|lock_hash| proposal_data.pause_list_additions.contains(lock_hash))
+    {
+        return Err(Error::InvalidProposalData);
+    }
+    Ok(proposal_data)
+}
+
+/// Finds a proposal of the same token consumed by the current transaction that targets the pause list cell of `registry_type_hash`.
+pub fn load_input_proposal(
+    registry_type_hash: &[u8; 32],
+) -> Result<Option<UDTPauseProposalData>, Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    for (index, type_script) in QueryIter::new(load_cell_type, Source::Input).enumerate() {
+        let type_script = match type_script {
+            Some(type_script) => type_script,
+            None => continue,
+        };
+        let type_args: Bytes = type_script.args().unpack();
+        if type_script.code_hash().as_slice() != script.code_hash().as_slice()
+            || type_script.hash_type().as_slice() != script.hash_type().as_slice()
+            || !registry::is_proposal_script(&type_args)
+            || registry::owner_args(&type_args)? != registry::owner_args(&args)?
+        {
+            continue;
+        }
+        let proposal_data: UDTPauseProposalData =
+            from_slice(&load_cell_data(index, Source::Input)?, false)?;
+        if proposal_data.registry_type_hash == *registry_type_hash {
+            return Ok(Some(proposal_data));
+        }
+    }
+    Ok(None)
+}
+
+// NOTE: Anyone may open a proposal, and approvals can only be added, each one by an input locked by the approver. Whether approvers belong to the targeted pause list cell is only checked when the proposal is executed, by that cell's own validation.
+pub fn verify_proposal_update() -> Result<(), Error> {
+    debug!("Entered verify_proposal_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = registry::verify_type_id(&script)?;
+
+    if output_count == 0 {
+        debug!("Consuming proposal cell");
+        let old_proposal_data: UDTPauseProposalData =
+            from_slice(&load_cell_data(0, Source::GroupInput)?, false)?;
+        // Executed along with the targeted pause list cell, or cancelled in owner mode
+        if QueryIter::new(load_cell_type_hash, Source::Output)
+            .any(|type_hash| type_hash == Some(old_proposal_data.registry_type_hash))
+            || check_owner_mode(&registry::owner_args(&args)?)?
+        {
+            return Ok(());
+        }
+        return Err(Error::NoPausePermission);
+    }
+
+    let new_proposal_data = load_canonical_proposal_data(0, Source::GroupOutput)?;
+    let old_approvals: Vec<[u8; 32]> = match input_count {
+        0 => Vec::new(),
+        _ => {
+            let old_proposal_data: UDTPauseProposalData =
+                from_slice(&load_cell_data(0, Source::GroupInput)?, false)?;
+            if !old_proposal_data.is_same_change(&new_proposal_data) {
+                return Err(Error::InvalidProposalData);
+            }
+            old_proposal_data.approvals
+        }
+    };
+    if old_approvals.iter().any(|lock_hash| {
+        new_proposal_data
+            .approvals
+            .binary_search(lock_hash)
+            .is_err()
+    }) {
+        return Err(Error::InvalidProposalData);
+    }
+    let input_lock_hashes: Vec<[u8; 32]> =
+        QueryIter::new(load_cell_lock_hash, Source::Input).collect();
+    for lock_hash in new_proposal_data.approvals.iter() {
+        if !old_approvals.contains(lock_hash) && !input_lock_hashes.contains(lock_hash) {
+            debug!(
+                "Approval without its lock among the inputs: {:?}",
+                lock_hash
+            );
+            return Err(Error::InvalidApproval);
+        }
+    }
+    Ok(())
+}
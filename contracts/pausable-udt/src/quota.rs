@@ -0,0 +1,173 @@
+use crate::config::{QUOTA_CELL_TYPE_ARGS, QUOTA_CELL_TYPE_CODE_HASH, QUOTA_CELL_TYPE_HASH_TYPE};
+use crate::error::Error;
+use crate::registry::{self, QUOTA_KIND};
+use crate::utils::{check_owner_mode, collect_amount_by_type_hash, parse_config_script};
+use alloc::vec::Vec;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::Script;
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::high_level::{
+    load_cell_data, load_cell_lock_hash, load_cell_type, load_script, load_script_hash, QueryIter,
+};
+use serde_molecule::{from_slice, to_vec};
+
+pub use crate::cell_data::{MinterQuota, UDTQuotaData};
+
+impl UDTQuotaData {
+    pub fn remaining_quota(&self, lock_hash: &[u8; 32]) -> Option<u128> {
+        self.quotas
+            .binary_search_by(|quota| quota.lock_hash.cmp(lock_hash))
+            .ok()
+            .map(|index| self.quotas[index].remaining_quota)
+    }
+
+    /// Returns the first input lock hash of the current transaction that has a quota.
+    pub fn find_input_minter(&self) -> Option<[u8; 32]> {
+        QueryIter::new(load_cell_lock_hash, Source::Input)
+            .find(|lock_hash| self.remaining_quota(lock_hash).is_some())
+    }
+}
+
+pub fn get_quota_script() -> Result<Option<Script>, Error> {
+    parse_config_script(
+        QUOTA_CELL_TYPE_CODE_HASH,
+        QUOTA_CELL_TYPE_HASH_TYPE,
+        QUOTA_CELL_TYPE_ARGS,
+    )
+}
+
+pub fn build_quota_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let registry_script = registry::build_registry_script(script, type_id)?;
+    let mut args: Vec<u8> = registry_script.args().unpack();
+    args.push(QUOTA_KIND);
+    Ok(registry_script.as_builder().args(args.pack()).build())
+}
+
+fn load_canonical_quota_data(index: usize, source: Source) -> Result<UDTQuotaData, Error> {
+    let data = load_cell_data(index, source)?;
+    let quota_data: UDTQuotaData = from_slice(&data, false).map_err(|_| Error::InvalidQuotaData)?;
+    if to_vec(&quota_data, false)? != data {
+        return Err(Error::InvalidQuotaData);
+    }
+    if !quota_data
+        .quotas
+        .windows(2)
+        .all(|pair| pair[0].lock_hash < pair[1].lock_hash)
+    {
+        return Err(Error::InvalidQuotaData);
+    }
+    Ok(quota_data)
+}
+
+/// Loads the quota data consumed by the current transaction. Returns `None` when quotas are not configured, and fails when the quota cell is not consumed.
+pub fn load_input_quota_data() -> Result<Option<UDTQuotaData>, Error> {
+    let quota_script = match get_quota_script()? {
+        Some(script) => script,
+        None => return Ok(None),
+    };
+    let index = QueryIter::new(load_cell_type, Source::Input)
+        .position(|type_script| type_script.as_ref() == Some(&quota_script))
+        .ok_or(Error::QuotaNotTracked)?;
+    Ok(Some(from_slice(
+        &load_cell_data(index, Source::Input)?,
+        false,
+    )?))
+}
+
+/// Requires the quota cell to be consumed and updated by any token transaction that mints, in owner mode too.
+pub fn verify_quota_tracked(inputs_amount: u128, outputs_amount: u128) -> Result<(), Error> {
+    debug!("Entered verify_quota_tracked");
+    let quota_script = match get_quota_script()? {
+        Some(script) => script,
+        None => return Ok(()),
+    };
+    if outputs_amount <= inputs_amount {
+        return Ok(());
+    }
+    let quota_data = load_input_quota_data()?.ok_or(Error::QuotaNotTracked)?;
+    if quota_data.udt_type_hash != load_script_hash()? {
+        return Err(Error::InvalidQuotaData);
+    }
+    if !QueryIter::new(load_cell_type, Source::Output)
+        .any(|type_script| type_script.as_ref() == Some(&quota_script))
+    {
+        return Err(Error::QuotaNotTracked);
+    }
+    Ok(())
+}
+
+pub fn verify_quota_update() -> Result<(), Error> {
+    debug!("Entered verify_quota_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = registry::verify_type_id(&script)?;
+
+    let is_owner_mode = check_owner_mode(&registry::owner_args(&args)?)?;
+    if input_count == 0 || output_count == 0 {
+        debug!("Creating or destroying quota cell");
+        if !is_owner_mode {
+            return Err(Error::NoMintPermission);
+        }
+    }
+    if output_count == 0 {
+        return Ok(());
+    }
+
+    let new_quota_data = load_canonical_quota_data(0, Source::GroupOutput)?;
+    if input_count == 0 {
+        return Ok(());
+    }
+    let old_quota_data = load_canonical_quota_data(0, Source::GroupInput)?;
+    if old_quota_data.udt_type_hash != new_quota_data.udt_type_hash {
+        return Err(Error::InvalidQuotaData);
+    }
+    let inputs_amount = collect_amount_by_type_hash(&new_quota_data.udt_type_hash, Source::Input)?;
+    let outputs_amount =
+        collect_amount_by_type_hash(&new_quota_data.udt_type_hash, Source::Output)?;
+    let minted_amount = outputs_amount.saturating_sub(inputs_amount);
+    // Owner mode may only change quotas freely in transactions that do not mint, since every mint spends a quota, the owner's own included
+    if is_owner_mode && minted_amount == 0 {
+        return Ok(());
+    }
+
+    // Otherwise only the quota of one minter among the inputs may go down, by exactly the minted amount, and out of owner mode the lock stays the same
+    if !is_owner_mode
+        && load_cell_lock_hash(0, Source::GroupOutput)?
+            != load_cell_lock_hash(0, Source::GroupInput)?
+    {
+        return Err(Error::InvalidQuotaData);
+    }
+    if old_quota_data.quotas.len() != new_quota_data.quotas.len() {
+        return Err(Error::InvalidQuotaData);
+    }
+    let mut spent_quota: u128 = 0;
+    for (old_quota, new_quota) in old_quota_data
+        .quotas
+        .iter()
+        .zip(new_quota_data.quotas.iter())
+    {
+        if old_quota == new_quota {
+            continue;
+        }
+        if old_quota.lock_hash != new_quota.lock_hash
+            || spent_quota != 0
+            || !QueryIter::new(load_cell_lock_hash, Source::Input)
+                .any(|lock_hash| lock_hash == old_quota.lock_hash)
+        {
+            return Err(Error::InvalidQuotaData);
+        }
+        spent_quota = old_quota
+            .remaining_quota
+            .checked_sub(new_quota.remaining_quota)
+            .ok_or(Error::InvalidQuotaData)?;
+    }
+    if spent_quota != minted_amount {
+        debug!(
+            "spent_quota: {}, minted_amount: {}",
+            spent_quota, minted_amount
+        );
+        return Err(Error::ExceededMintQuota);
+    }
+    Ok(())
+}
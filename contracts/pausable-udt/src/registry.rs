@@ -0,0 +1,989 @@
+use crate::config::UNPAUSE_DELAY_EPOCHS;
+use crate::error::Error;
+use crate::proposal;
+use crate::roles::{self, ROLE_ADMIN, ROLE_PAUSER, ROLE_UNPAUSER};
+use crate::utils::{
+    calc_type_id, check_owner_mode, current_epoch_lower_bound, input_age_epochs,
+    script_like_to_script, OWNER_MODE_FLAGS_LEN,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use ckb_ssri_std::public_module_traits::udt::UDTPausableData;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::{Byte32, OutPoint, Script};
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::high_level::{
+    load_cell, load_cell_data, load_cell_lock_hash, load_cell_type, load_cell_type_hash,
+    load_header, load_input, load_script, load_script_hash, QueryIter,
+};
+use ckb_std::since::EpochNumberWithFraction;
+use serde::{Deserialize, Serialize};
+use serde_molecule::molecule::{assemble_table, disassemble_table};
+use serde_molecule::{dynvec_serde, from_slice, to_vec};
+
+pub use crate::cell_data::{
+    FrozenCell, GlobalPause, LockCodeHashRule, PausableRegistryData, PauseExpiry, PauseRestriction,
+    PendingUnpause,
+};
+
+// NOTE: External pause list cells can be typed with this same binary. Their args are <owner lock hash> [<owner mode flags>] <type id>, which is how they are told apart from token cells. The flags are copied from the token args, so that owner mode is detected the same way for the token and all its registry cells.
+pub const REGISTRY_ARGS_LEN: usize = 64;
+pub const FLAGGED_REGISTRY_ARGS_LEN: usize = REGISTRY_ARGS_LEN + OWNER_MODE_FLAGS_LEN;
+
+// SMT registry and supply cells append one kind byte to the registry args.
+pub const SMT_REGISTRY_ARGS_LEN: usize = 65;
+pub const FLAGGED_SMT_REGISTRY_ARGS_LEN: usize = SMT_REGISTRY_ARGS_LEN + OWNER_MODE_FLAGS_LEN;
+pub const SMT_REGISTRY_KIND: u8 = 1;
+pub const SUPPLY_KIND: u8 = 2;
+pub const ROLES_KIND: u8 = 3;
+pub const ALLOWLIST_KIND: u8 = 4;
+pub const QUOTA_KIND: u8 = 5;
+pub const PROPOSAL_KIND: u8 = 6;
+
+// Builders add one input per required approver with this index and the approver lock hash as tx hash, for wallets to replace with a live cell of that lock. Such an out point never exists, so a transaction still holding one is rejected by any node: callers must replace every placeholder before signing.
+pub const APPROVER_PLACEHOLDER_INDEX: u32 = u32::MAX;
+
+pub const RESTRICTION_NONE: u8 = 0;
+pub const RESTRICTION_BLOCK_SEND: u8 = 0b01;
+pub const RESTRICTION_BLOCK_RECEIVE: u8 = 0b10;
+pub const RESTRICTION_FULL_FREEZE: u8 = RESTRICTION_BLOCK_SEND | RESTRICTION_BLOCK_RECEIVE;
+
+impl LockCodeHashRule {
+    pub fn matches(&self, lock: &Script) -> bool {
+        lock.code_hash().as_slice() == self.code_hash
+            && u8::from(lock.hash_type()) == self.hash_type
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LockCodeHashRules {
+    pub rules: Vec<LockCodeHashRule>,
+}
+
+impl From<&OutPoint> for FrozenCell {
+    fn from(out_point: &OutPoint) -> Self {
+        FrozenCell {
+            tx_hash: out_point.tx_hash().unpack(),
+            index: out_point.index().unpack(),
+        }
+    }
+}
+
+/// A pause entry as reported by `UDTPausable.enumerate_pause_entries`. `expiry_epoch` is 0 for entries that do not expire.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PauseEntry {
+    pub lock_hash: [u8; 32],
+    pub level: u8,
+    pub expiry_epoch: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PauseEntries {
+    #[serde(with = "dynvec_serde")]
+    pub entries: Vec<PauseEntry>,
+}
+
+impl GlobalPause {
+    // NOTE: Header deps and `since` only prove that the current epoch is at least `current_epoch`, never that it is below some epoch. A global pause therefore has no start epoch, and it stays active unless the transaction proves its end has been reached.
+    pub fn is_active(&self, current_epoch: Option<u64>) -> bool {
+        self.end_epoch == 0 || current_epoch.is_none_or(|epoch| epoch < self.end_epoch)
+    }
+}
+
+impl From<UDTPausableData> for PausableRegistryData {
+    fn from(data: UDTPausableData) -> Self {
+        PausableRegistryData {
+            pause_list: data.pause_list,
+            next_type_script: data.next_type_script,
+            ..Default::default()
+        }
+    }
+}
+
+impl PausableRegistryData {
+    pub fn restriction_level(&self, lock_hash: &[u8; 32]) -> u8 {
+        if self.pause_list.contains(lock_hash) {
+            return RESTRICTION_FULL_FREEZE;
+        }
+        self.restriction_list
+            .iter()
+            .filter(|restriction| restriction.lock_hash == *lock_hash)
+            .fold(RESTRICTION_NONE, |level, restriction| {
+                level | restriction.level
+            })
+    }
+
+    /// Adds `level` to the restriction of `lock_hash`, moving the entry to `pause_list` once it amounts to a full freeze. Both lists stay sorted.
+    pub fn add_restriction(&mut self, lock_hash: [u8; 32], level: u8) {
+        let combined_level = self.restriction_level(&lock_hash) | level;
+        self.restriction_list
+            .retain(|restriction| restriction.lock_hash != lock_hash);
+        if combined_level == RESTRICTION_FULL_FREEZE {
+            if let Err(index) = self.pause_list.binary_search(&lock_hash) {
+                self.pause_list.insert(index, lock_hash);
+            }
+        } else {
+            let index = self
+                .restriction_list
+                .partition_point(|restriction| restriction.lock_hash < lock_hash);
+            self.restriction_list.insert(
+                index,
+                PauseRestriction {
+                    lock_hash,
+                    level: combined_level,
+                },
+            );
+        }
+    }
+
+    pub fn expiry_epoch(&self, lock_hash: &[u8; 32]) -> Option<u64> {
+        self.expiry_list
+            .iter()
+            .find(|expiry| expiry.lock_hash == *lock_hash)
+            .map(|expiry| expiry.expiry_epoch)
+    }
+
+    /// Like `restriction_level`, but an entry no longer applies once `current_epoch` has reached its expiry.
+    pub fn active_restriction_level(&self, lock_hash: &[u8; 32], current_epoch: Option<u64>) -> u8 {
+        match (self.expiry_epoch(lock_hash), current_epoch) {
+            (Some(expiry_epoch), Some(epoch)) if epoch >= expiry_epoch => RESTRICTION_NONE,
+            _ => self.restriction_level(lock_hash),
+        }
+    }
+
+    /// Sets or clears the expiry of the entry of `lock_hash`. The list stays sorted.
+    pub fn set_expiry(&mut self, lock_hash: [u8; 32], expiry_epoch: Option<u64>) {
+        self.expiry_list
+            .retain(|expiry| expiry.lock_hash != lock_hash);
+        if let Some(expiry_epoch) = expiry_epoch {
+            let index = self
+                .expiry_list
+                .partition_point(|expiry| expiry.lock_hash < lock_hash);
+            self.expiry_list.insert(
+                index,
+                PauseExpiry {
+                    lock_hash,
+                    expiry_epoch,
+                },
+            );
+        }
+    }
+
+    /// Removes the entries that have expired at `current_epoch`, and returns whether there was any.
+    pub fn remove_expired(&mut self, current_epoch: u64) -> bool {
+        let expired_lock_hashes: Vec<[u8; 32]> = self
+            .expiry_list
+            .iter()
+            .filter(|expiry| current_epoch >= expiry.expiry_epoch)
+            .map(|expiry| expiry.lock_hash)
+            .collect();
+        self.remove_restrictions(&expired_lock_hashes);
+        !expired_lock_hashes.is_empty()
+    }
+
+    pub fn entries(&self) -> Vec<PauseEntry> {
+        self.pause_list
+            .iter()
+            .map(|lock_hash| (*lock_hash, RESTRICTION_FULL_FREEZE))
+            .chain(
+                self.restriction_list
+                    .iter()
+                    .map(|restriction| (restriction.lock_hash, restriction.level)),
+            )
+            .map(|(lock_hash, level)| PauseEntry {
+                lock_hash,
+                level,
+                expiry_epoch: self.expiry_epoch(&lock_hash).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    pub fn contains_any(&self, lock_hashes: &[[u8; 32]]) -> bool {
+        lock_hashes
+            .iter()
+            .any(|lock_hash| self.restriction_level(lock_hash) != RESTRICTION_NONE)
+    }
+
+    pub fn is_cell_frozen(&self, frozen_cell: &FrozenCell) -> bool {
+        self.frozen_cells.binary_search(frozen_cell).is_ok()
+    }
+
+    /// Adds `frozen_cell` to `frozen_cells`, which stays sorted. Returns whether it was not frozen yet.
+    pub fn freeze_cell(&mut self, frozen_cell: FrozenCell) -> bool {
+        match self.frozen_cells.binary_search(&frozen_cell) {
+            Ok(_) => false,
+            Err(index) => {
+                self.frozen_cells.insert(index, frozen_cell);
+                true
+            }
+        }
+    }
+
+    /// Removes `frozen_cells` from this cell, and returns whether any of them was frozen here.
+    pub fn unfreeze_cells(&mut self, frozen_cells: &[FrozenCell]) -> bool {
+        let frozen_count = self.frozen_cells.len();
+        self.frozen_cells
+            .retain(|frozen_cell| !frozen_cells.contains(frozen_cell));
+        self.frozen_cells.len() != frozen_count
+    }
+
+    /// Adds `rule` to `lock_code_hash_rules`, which stays sorted. Returns whether it was not there yet.
+    pub fn add_lock_code_hash_rule(&mut self, rule: LockCodeHashRule) -> bool {
+        match self.lock_code_hash_rules.binary_search(&rule) {
+            Ok(_) => false,
+            Err(index) => {
+                self.lock_code_hash_rules.insert(index, rule);
+                true
+            }
+        }
+    }
+
+    /// Removes `rules` from this cell, and returns whether any of them was here.
+    pub fn remove_lock_code_hash_rules(&mut self, rules: &[LockCodeHashRule]) -> bool {
+        let rules_count = self.lock_code_hash_rules.len();
+        self.lock_code_hash_rules
+            .retain(|rule| !rules.contains(rule));
+        self.lock_code_hash_rules.len() != rules_count
+    }
+
+    pub fn is_type_blocked(&self, type_hash: &[u8; 32]) -> bool {
+        self.blocked_type_hashes.binary_search(type_hash).is_ok()
+    }
+
+    /// Adds `type_hash` to `blocked_type_hashes`, which stays sorted. Returns whether it was not blocked yet.
+    pub fn block_type_hash(&mut self, type_hash: [u8; 32]) -> bool {
+        match self.blocked_type_hashes.binary_search(&type_hash) {
+            Ok(_) => false,
+            Err(index) => {
+                self.blocked_type_hashes.insert(index, type_hash);
+                true
+            }
+        }
+    }
+
+    /// Removes `type_hashes` from this cell, and returns whether any of them was blocked here.
+    pub fn unblock_type_hashes(&mut self, type_hashes: &[[u8; 32]]) -> bool {
+        let blocked_count = self.blocked_type_hashes.len();
+        self.blocked_type_hashes
+            .retain(|type_hash| !type_hashes.contains(type_hash));
+        self.blocked_type_hashes.len() != blocked_count
+    }
+
+    pub fn is_redemption_lock(&self, lock_hash: &[u8; 32]) -> bool {
+        self.redemption_lock_hashes.binary_search(lock_hash).is_ok()
+    }
+
+    /// Adds `lock_hash` to `redemption_lock_hashes`, which stays sorted. Returns whether it was not there yet.
+    pub fn add_redemption_lock_hash(&mut self, lock_hash: [u8; 32]) -> bool {
+        match self.redemption_lock_hashes.binary_search(&lock_hash) {
+            Ok(_) => false,
+            Err(index) => {
+                self.redemption_lock_hashes.insert(index, lock_hash);
+                true
+            }
+        }
+    }
+
+    /// Removes `lock_hashes` from this cell, and returns whether any of them was a redemption lock here.
+    pub fn remove_redemption_lock_hashes(&mut self, lock_hashes: &[[u8; 32]]) -> bool {
+        let redemption_count = self.redemption_lock_hashes.len();
+        self.redemption_lock_hashes
+            .retain(|lock_hash| !lock_hashes.contains(lock_hash));
+        self.redemption_lock_hashes.len() != redemption_count
+    }
+
+    pub fn remove_restrictions(&mut self, lock_hashes: &[[u8; 32]]) {
+        self.pause_list
+            .retain(|lock_hash| !lock_hashes.contains(lock_hash));
+        self.restriction_list
+            .retain(|restriction| !lock_hashes.contains(&restriction.lock_hash));
+        self.expiry_list
+            .retain(|expiry| !lock_hashes.contains(&expiry.lock_hash));
+        self.pending_unpauses
+            .retain(|pending_unpause| !lock_hashes.contains(&pending_unpause.lock_hash));
+    }
+
+    /// Counts the distinct approvers of this cell among the input locks of the current transaction.
+    pub fn count_input_approvals(&self) -> usize {
+        let mut approved_lock_hashes: Vec<[u8; 32]> =
+            QueryIter::new(load_cell_lock_hash, Source::Input)
+                .filter(|lock_hash| self.approvers.binary_search(lock_hash).is_ok())
+                .collect();
+        approved_lock_hashes.sort();
+        approved_lock_hashes.dedup();
+        approved_lock_hashes.len()
+    }
+
+    pub fn pending_unpause(&self, lock_hash: &[u8; 32]) -> Option<&PendingUnpause> {
+        self.pending_unpauses
+            .binary_search_by(|pending_unpause| pending_unpause.lock_hash.cmp(lock_hash))
+            .ok()
+            .map(|index| &self.pending_unpauses[index])
+    }
+
+    pub fn is_unpause_pending(&self, lock_hash: &[u8; 32]) -> bool {
+        self.pending_unpause(lock_hash).is_some()
+    }
+
+    /// Marks the entry of `lock_hash` in this cell as pending unpause. Returns whether there is such an entry that was not pending yet.
+    pub fn schedule_unpause(&mut self, lock_hash: [u8; 32]) -> bool {
+        if self.restriction_level(&lock_hash) == RESTRICTION_NONE {
+            return false;
+        }
+        match self
+            .pending_unpauses
+            .binary_search_by(|pending_unpause| pending_unpause.lock_hash.cmp(&lock_hash))
+        {
+            Ok(_) => false,
+            Err(index) => {
+                self.pending_unpauses.insert(
+                    index,
+                    PendingUnpause {
+                        lock_hash,
+                        scheduled_epoch: 0,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Removes the entries of `lock_hashes` that are pending unpause in this cell, and returns whether there was any.
+    pub fn finalize_unpauses(&mut self, lock_hashes: &[[u8; 32]]) -> bool {
+        let finalized_lock_hashes: Vec<[u8; 32]> = lock_hashes
+            .iter()
+            .filter(|lock_hash| self.is_unpause_pending(lock_hash))
+            .cloned()
+            .collect();
+        self.remove_restrictions(&finalized_lock_hashes);
+        !finalized_lock_hashes.is_empty()
+    }
+}
+
+/// Combines the restriction level of each lock hash over a loaded pause list chain, leaving out entries expired at `current_epoch`.
+pub fn restriction_levels(
+    chain: &[PausableRegistryData],
+    lock_hashes: &[[u8; 32]],
+    current_epoch: Option<u64>,
+) -> Vec<u8> {
+    lock_hashes
+        .iter()
+        .map(|lock_hash| {
+            chain.iter().fold(RESTRICTION_NONE, |level, pausable_data| {
+                level | pausable_data.active_restriction_level(lock_hash, current_epoch)
+            })
+        })
+        .collect()
+}
+
+/// Tells whether each cell is frozen by any cell of a loaded pause list chain.
+pub fn frozen_cells(chain: &[PausableRegistryData], frozen_cells: &[FrozenCell]) -> Vec<bool> {
+    frozen_cells
+        .iter()
+        .map(|frozen_cell| {
+            chain
+                .iter()
+                .any(|pausable_data| pausable_data.is_cell_frozen(frozen_cell))
+        })
+        .collect()
+}
+
+/// Tells whether each lock matches a lock code hash rule of a loaded pause list chain.
+pub fn lock_code_hash_paused(chain: &[PausableRegistryData], locks: &[Script]) -> Vec<bool> {
+    locks
+        .iter()
+        .map(|lock| {
+            chain.iter().any(|pausable_data| {
+                pausable_data
+                    .lock_code_hash_rules
+                    .iter()
+                    .any(|rule| rule.matches(lock))
+            })
+        })
+        .collect()
+}
+
+/// Lists the lock code hash rules of a loaded pause list chain.
+pub fn lock_code_hash_rules(chain: &[PausableRegistryData]) -> Vec<LockCodeHashRule> {
+    chain
+        .iter()
+        .flat_map(|pausable_data| pausable_data.lock_code_hash_rules.iter().cloned())
+        .collect()
+}
+
+/// Tells whether each lock hash is a redemption lock in any cell of a loaded pause list chain.
+pub fn redemption_lock_hashes(
+    chain: &[PausableRegistryData],
+    lock_hashes: &[[u8; 32]],
+) -> Vec<bool> {
+    lock_hashes
+        .iter()
+        .map(|lock_hash| {
+            chain
+                .iter()
+                .any(|pausable_data| pausable_data.is_redemption_lock(lock_hash))
+        })
+        .collect()
+}
+
+/// Tells whether each type script hash is blocked by any cell of a loaded pause list chain.
+pub fn blocked_type_hashes(chain: &[PausableRegistryData], type_hashes: &[[u8; 32]]) -> Vec<bool> {
+    type_hashes
+        .iter()
+        .map(|type_hash| {
+            chain
+                .iter()
+                .any(|pausable_data| pausable_data.is_type_blocked(type_hash))
+        })
+        .collect()
+}
+
+/// Returns the global pause of a loaded pause list chain, if any cell sets one.
+pub fn global_pause(chain: &[PausableRegistryData]) -> Option<GlobalPause> {
+    chain
+        .iter()
+        .find_map(|pausable_data| pausable_data.global_pause.clone())
+}
+
+/// Decodes pause list cell data of any version. Fields missing from data written before they existed take their default values.
+pub fn decode_registry_data(data: &[u8]) -> Result<PausableRegistryData, Error> {
+    let mut parts: Vec<Vec<u8>> = disassemble_table(data)?
+        .into_iter()
+        .map(|part| part.to_vec())
+        .collect();
+    let default_data = to_vec(&PausableRegistryData::default(), false)?;
+    for default_part in disassemble_table(&default_data)?
+        .into_iter()
+        .skip(parts.len())
+    {
+        parts.push(default_part.to_vec());
+    }
+    Ok(from_slice(&assemble_table(&parts), false)?)
+}
+
+/// Returns the length of the owner args at the start of registry args, and the kind byte of kinded registry cells.
+fn registry_args_layout(args: &Bytes) -> Option<(usize, Option<u8>)> {
+    match args.len() {
+        REGISTRY_ARGS_LEN => Some((32, None)),
+        FLAGGED_REGISTRY_ARGS_LEN => Some((32 + OWNER_MODE_FLAGS_LEN, None)),
+        SMT_REGISTRY_ARGS_LEN => Some((32, Some(args[REGISTRY_ARGS_LEN]))),
+        FLAGGED_SMT_REGISTRY_ARGS_LEN => Some((
+            32 + OWNER_MODE_FLAGS_LEN,
+            Some(args[FLAGGED_REGISTRY_ARGS_LEN]),
+        )),
+        _ => None,
+    }
+}
+
+fn is_kind(args: &Bytes, kind: u8) -> bool {
+    matches!(registry_args_layout(args), Some((_, Some(args_kind))) if args_kind == kind)
+}
+
+pub fn is_registry_script(args: &Bytes) -> bool {
+    matches!(registry_args_layout(args), Some((_, None)))
+}
+
+pub fn is_smt_registry_script(args: &Bytes) -> bool {
+    is_kind(args, SMT_REGISTRY_KIND)
+}
+
+pub fn is_supply_script(args: &Bytes) -> bool {
+    is_kind(args, SUPPLY_KIND)
+}
+
+pub fn is_roles_script(args: &Bytes) -> bool {
+    is_kind(args, ROLES_KIND)
+}
+
+pub fn is_allowlist_script(args: &Bytes) -> bool {
+    is_kind(args, ALLOWLIST_KIND)
+}
+
+pub fn is_quota_script(args: &Bytes) -> bool {
+    is_kind(args, QUOTA_KIND)
+}
+
+pub fn is_proposal_script(args: &Bytes) -> bool {
+    is_kind(args, PROPOSAL_KIND)
+}
+
+/// Returns the owner hash and owner mode flags of registry args, in the token args layout expected by `check_owner_mode`.
+pub fn owner_args(args: &Bytes) -> Result<Bytes, Error> {
+    let (owner_args_len, _) = registry_args_layout(args).ok_or(Error::InvalidRegistryArgs)?;
+    Ok(args.slice(..owner_args_len))
+}
+
+/// Builds the type script of a new pause list cell from the token (or pause list) script in context.
+pub fn build_registry_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let args: Bytes = script.args().unpack();
+    let owner_args = match registry_args_layout(&args) {
+        Some((owner_args_len, _)) => args.slice(..owner_args_len),
+        None if args.len() == 32 => args,
+        None if args.len() == 32 + OWNER_MODE_FLAGS_LEN => args,
+        None => return Err(Error::InvalidTokenArgs),
+    };
+    let mut registry_args = owner_args.to_vec();
+    registry_args.extend_from_slice(&type_id);
+    Ok(script
+        .clone()
+        .as_builder()
+        .args(registry_args.pack())
+        .build())
+}
+
+/// Checks the Type ID semantics shared by every kind of registry cell, and returns the group input and output counts.
+pub fn verify_type_id(script: &Script) -> Result<(usize, usize), Error> {
+    let args: Bytes = script.args().unpack();
+
+    let input_count = QueryIter::new(load_cell, Source::GroupInput).count();
+    let output_count = QueryIter::new(load_cell, Source::GroupOutput).count();
+    debug!(
+        "input_count: {}, output_count: {}",
+        input_count, output_count
+    );
+    if input_count > 1 || output_count > 1 {
+        return Err(Error::InvalidTypeId);
+    }
+
+    if input_count == 0 {
+        let first_cell_input = load_input(0, Source::Input)?;
+        let script_hash = load_script_hash()?;
+        let output_index = QueryIter::new(load_cell_type_hash, Source::Output)
+            .position(|type_hash| type_hash == Some(script_hash))
+            .ok_or(Error::InvalidTypeId)?;
+        let owner_args_len = owner_args(&args)?.len();
+        if calc_type_id(&first_cell_input, output_index as u64)[..]
+            != args[owner_args_len..owner_args_len + 32]
+        {
+            return Err(Error::InvalidTypeId);
+        }
+    }
+
+    Ok((input_count, output_count))
+}
+
+/// Requires the PAUSER role to add restrictions and the UNPAUSER role to lift them when roles are configured, and any of PAUSER, UNPAUSER or ADMIN for other updates. Requires owner mode otherwise.
+pub fn verify_pause_authority(args: &Bytes, tightens: bool, loosens: bool) -> Result<(), Error> {
+    debug!("tightens: {}, loosens: {}", tightens, loosens);
+    match roles::load_roles()? {
+        Some(roles_data) => {
+            if tightens && !roles_data.inputs_have_role(ROLE_PAUSER) {
+                return Err(Error::NoPausePermission);
+            }
+            if loosens && !roles_data.inputs_have_role(ROLE_UNPAUSER) {
+                return Err(Error::NoUnpausePermission);
+            }
+            // Updates that neither add nor lift restrictions still need some administration role
+            if !tightens
+                && !loosens
+                && ![ROLE_PAUSER, ROLE_UNPAUSER, ROLE_ADMIN]
+                    .iter()
+                    .any(|role| roles_data.inputs_have_role(*role))
+            {
+                return Err(Error::NoPausePermission);
+            }
+        }
+        None => {
+            if !check_owner_mode(&owner_args(args)?)? {
+                return Err(Error::NoPausePermission);
+            }
+        }
+    }
+    Ok(())
+}
+
+// NOTE: The guardian of a pause list cell is a hot key for incident response. An input locked by it authorizes any update of that cell that only adds restrictions, but it can never lift one, and replacing the guardian itself counts as lifting restrictions.
+fn is_guardian_update(old: Option<&PausableRegistryData>, loosens: bool) -> bool {
+    match old.and_then(|pausable_data| pausable_data.guardian_lock_hash) {
+        Some(guardian_lock_hash) if !loosens => QueryIter::new(load_cell_lock_hash, Source::Input)
+            .any(|lock_hash| lock_hash == guardian_lock_hash),
+        _ => false,
+    }
+}
+
+// NOTE: Once a pause list cell sets an approval threshold, `approval_threshold` distinct approver locks among the inputs are what authorizes its updates, in place of the owner lock or roles. Changing the approvers or the threshold counts as lifting restrictions, so it needs the approvals of the current set.
+fn verify_update_authority(
+    args: &Bytes,
+    old: Option<&PausableRegistryData>,
+    tightens: bool,
+    loosens: bool,
+) -> Result<(), Error> {
+    if is_guardian_update(old, loosens) {
+        return Ok(());
+    }
+    match old {
+        Some(pausable_data) if pausable_data.approval_threshold > 0 => {
+            let approvals = pausable_data.count_input_approvals();
+            debug!(
+                "approvals: {}, approval_threshold: {}",
+                approvals, pausable_data.approval_threshold
+            );
+            if approvals < pausable_data.approval_threshold as usize {
+                return Err(Error::NotEnoughApprovals);
+            }
+            Ok(())
+        }
+        _ => verify_pause_authority(args, tightens, loosens),
+    }
+}
+
+/// Tells whether the update applies a proposal consumed in the same transaction. The proposal must have reached the approval threshold of the consumed cell, and the new data must be exactly the proposed change applied to the old one.
+fn is_executed_proposal(
+    old: Option<&PausableRegistryData>,
+    new: &PausableRegistryData,
+) -> Result<bool, Error> {
+    let old = match old {
+        Some(pausable_data) if pausable_data.approval_threshold > 0 => pausable_data,
+        _ => return Ok(false),
+    };
+    let proposal_data = match proposal::load_input_proposal(&load_script_hash()?)? {
+        Some(proposal_data) => proposal_data,
+        None => return Ok(false),
+    };
+    if proposal_data.count_approvals(&old.approvers) < old.approval_threshold as usize {
+        return Err(Error::ProposalNotApproved);
+    }
+    if to_vec(&proposal_data.apply(old), false)? != to_vec(new, false)? {
+        return Err(Error::ProposalMismatch);
+    }
+    Ok(true)
+}
+
+// NOTE: With `UNPAUSE_DELAY_EPOCHS`, an entry still in force may only be lifted or shortened once it is pending unpause in the consumed cell and the delay has passed since it was scheduled. Every cell holding a pending unpause was created at or after its scheduled epoch, so a consumed cell that has existed for the whole delay proves it for all of them. An update keeping an unpause pending may record the scheduled epoch of a `scheduled_epoch` of 0, proven by the header of the consumed cell, and must otherwise keep it or leave it at 0, which restarts the delay. Unlinking the rest of the chain would lift its entries at once, so it is rejected.
+#[allow(clippy::absurd_extreme_comparisons)]
+fn verify_unpause_delay(
+    old: &PausableRegistryData,
+    new: &PausableRegistryData,
+) -> Result<(), Error> {
+    if UNPAUSE_DELAY_EPOCHS == 0 {
+        return Ok(());
+    }
+    if let Some(ref old_next_type_script) = old.next_type_script {
+        if new.next_type_script.as_ref().map(script_like_to_script)
+            != Some(script_like_to_script(old_next_type_script))
+        {
+            return Err(Error::UnpauseNotDue);
+        }
+    }
+    let current_epoch = current_epoch_lower_bound()?;
+    let input_epoch = load_header(0, Source::GroupInput).ok().map(|header| {
+        EpochNumberWithFraction::from_full_value(header.raw().epoch().unpack()).number()
+    });
+    for pending_unpause in new.pending_unpauses.iter() {
+        if pending_unpause.scheduled_epoch == 0 {
+            continue;
+        }
+        // Recording a later epoch than the actual schedule only postpones the unpause
+        let scheduled_epoch = match old.pending_unpause(&pending_unpause.lock_hash) {
+            Some(old_pending_unpause) if old_pending_unpause.scheduled_epoch != 0 => {
+                Some(old_pending_unpause.scheduled_epoch)
+            }
+            Some(_) => input_epoch,
+            None => None,
+        };
+        if scheduled_epoch.is_none_or(|epoch| pending_unpause.scheduled_epoch < epoch) {
+            debug!("Invalid scheduled epoch: {:?}", pending_unpause);
+            return Err(Error::InvalidPauseData);
+        }
+    }
+    let input_age = input_age_epochs(0, Source::GroupInput, current_epoch)?;
+    for entry in old.entries().iter() {
+        if old.active_restriction_level(&entry.lock_hash, current_epoch) == RESTRICTION_NONE {
+            continue;
+        }
+        let new_level = new.restriction_level(&entry.lock_hash);
+        let old_expiry = old.expiry_epoch(&entry.lock_hash).unwrap_or(u64::MAX);
+        let new_expiry = new.expiry_epoch(&entry.lock_hash).unwrap_or(u64::MAX);
+        if entry.level & !new_level == 0 && new_expiry >= old_expiry {
+            continue;
+        }
+        let scheduled_epoch = match old.pending_unpause(&entry.lock_hash) {
+            Some(pending_unpause) => pending_unpause.scheduled_epoch,
+            None => {
+                debug!("Unpause not scheduled: {:?}", entry.lock_hash);
+                return Err(Error::UnpauseNotScheduled);
+            }
+        };
+        let is_due = input_age >= UNPAUSE_DELAY_EPOCHS
+            || (scheduled_epoch != 0
+                && current_epoch.is_some_and(|epoch| {
+                    epoch >= scheduled_epoch.saturating_add(UNPAUSE_DELAY_EPOCHS)
+                }));
+        if !is_due {
+            debug!("Unpause not due: {:?}", entry.lock_hash);
+            return Err(Error::UnpauseNotDue);
+        }
+    }
+    Ok(())
+}
+
+/// Tells whether going from `old` to `new` adds restrictions, lifts restrictions, or both. Expiries count as the time an entry keeps applying, and chain links as everything they lead to.
+pub fn pause_change(old: &PausableRegistryData, new: &PausableRegistryData) -> (bool, bool) {
+    let mut tightens = false;
+    let mut loosens = false;
+    for entry in old.entries().iter().chain(new.entries().iter()) {
+        let old_level = old.restriction_level(&entry.lock_hash);
+        let new_level = new.restriction_level(&entry.lock_hash);
+        tightens |= new_level & !old_level != 0;
+        loosens |= old_level & !new_level != 0;
+        if old_level != RESTRICTION_NONE && new_level != RESTRICTION_NONE {
+            // No expiry lasts longer than any expiry
+            let old_expiry = old.expiry_epoch(&entry.lock_hash).unwrap_or(u64::MAX);
+            let new_expiry = new.expiry_epoch(&entry.lock_hash).unwrap_or(u64::MAX);
+            tightens |= new_expiry > old_expiry;
+            loosens |= new_expiry < old_expiry;
+        }
+    }
+    tightens |= new
+        .frozen_cells
+        .iter()
+        .any(|frozen_cell| !old.is_cell_frozen(frozen_cell));
+    loosens |= old
+        .frozen_cells
+        .iter()
+        .any(|frozen_cell| !new.is_cell_frozen(frozen_cell));
+    tightens |= new
+        .lock_code_hash_rules
+        .iter()
+        .any(|rule| !old.lock_code_hash_rules.contains(rule));
+    loosens |= old
+        .lock_code_hash_rules
+        .iter()
+        .any(|rule| !new.lock_code_hash_rules.contains(rule));
+    tightens |= new
+        .blocked_type_hashes
+        .iter()
+        .any(|type_hash| !old.is_type_blocked(type_hash));
+    loosens |= old
+        .blocked_type_hashes
+        .iter()
+        .any(|type_hash| !new.is_type_blocked(type_hash));
+    // A redemption lock lets paused holders move their funds to it
+    loosens |= new
+        .redemption_lock_hashes
+        .iter()
+        .any(|lock_hash| !old.is_redemption_lock(lock_hash));
+    tightens |= old
+        .redemption_lock_hashes
+        .iter()
+        .any(|lock_hash| !new.is_redemption_lock(lock_hash));
+    loosens |= old.guardian_lock_hash != new.guardian_lock_hash;
+    loosens |= old.approvers != new.approvers || old.approval_threshold != new.approval_threshold;
+    // Scheduling an unpause needs the authority to unpause, while cancelling one keeps the entry in force
+    loosens |= new
+        .pending_unpauses
+        .iter()
+        .any(|pending_unpause| !old.is_unpause_pending(&pending_unpause.lock_hash));
+    tightens |= old.pending_unpauses.iter().any(|pending_unpause| {
+        !new.is_unpause_pending(&pending_unpause.lock_hash)
+            && new.restriction_level(&pending_unpause.lock_hash) != RESTRICTION_NONE
+    });
+    if old.global_pause != new.global_pause {
+        tightens |= new.global_pause.is_some();
+        loosens |= old.global_pause.is_some();
+    }
+    if old.next_type_script.as_ref().map(script_like_to_script)
+        != new.next_type_script.as_ref().map(script_like_to_script)
+    {
+        tightens |= new.next_type_script.is_some();
+        loosens |= old.next_type_script.is_some();
+    }
+    (tightens, loosens)
+}
+
+pub fn verify_registry_update() -> Result<(), Error> {
+    debug!("Entered verify_registry_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = verify_type_id(&script)?;
+
+    let old_pausable_data: Option<PausableRegistryData> = match input_count {
+        0 => None,
+        _ => Some(decode_registry_data(&load_cell_data(
+            0,
+            Source::GroupInput,
+        )?)?),
+    };
+
+    if output_count == 0 {
+        debug!("Destroying pause list cell");
+        let (tightens, loosens) = pause_change(
+            old_pausable_data
+                .as_ref()
+                .unwrap_or(&PausableRegistryData::default()),
+            &PausableRegistryData::default(),
+        );
+        if let Some(ref old_pausable_data) = old_pausable_data {
+            verify_unpause_delay(old_pausable_data, &PausableRegistryData::default())?;
+        }
+        return verify_update_authority(&args, old_pausable_data.as_ref(), tightens, loosens);
+    }
+
+    let new_pausable_data = load_canonical_pausable_data()?;
+    let (tightens, loosens) = pause_change(
+        old_pausable_data
+            .as_ref()
+            .unwrap_or(&PausableRegistryData::default()),
+        &new_pausable_data,
+    );
+    if !is_executed_proposal(old_pausable_data.as_ref(), &new_pausable_data)? {
+        verify_update_authority(&args, old_pausable_data.as_ref(), tightens, loosens)?;
+    }
+    if let Some(ref old_pausable_data) = old_pausable_data {
+        verify_unpause_delay(old_pausable_data, &new_pausable_data)?;
+    }
+    if !new_pausable_data
+        .pending_unpauses
+        .iter()
+        .all(|pending_unpause| {
+            new_pausable_data.restriction_level(&pending_unpause.lock_hash) != RESTRICTION_NONE
+        })
+    {
+        return Err(Error::InvalidPauseData);
+    }
+
+    if let Some(ref next_type_script_like) = new_pausable_data.next_type_script {
+        let next_type_script = script_like_to_script(next_type_script_like);
+        if next_type_script == script {
+            return Err(Error::CyclicPauseList);
+        }
+        let is_next_unchanged = old_pausable_data
+            .and_then(|data| data.next_type_script)
+            .map(|old_next| script_like_to_script(&old_next) == next_type_script)
+            .unwrap_or(false);
+        if !is_next_unchanged {
+            verify_acyclic_chain(&script, next_type_script)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads the output pause list and rejects anything that would not serialize back to the same bytes, or that is not strictly ascending. Data of an earlier layout, such as a plain `UDTPausableData`, is accepted as long as the fields it has are canonical.
+fn load_canonical_pausable_data() -> Result<PausableRegistryData, Error> {
+    let new_data = load_cell_data(0, Source::GroupOutput)?;
+    let new_pausable_data = decode_registry_data(&new_data).map_err(|_| Error::InvalidPauseData)?;
+    let field_count = disassemble_table(&new_data)?.len();
+    let mut canonical_parts: Vec<Vec<u8>> = disassemble_table(&to_vec(&new_pausable_data, false)?)?
+        .into_iter()
+        .map(|part| part.to_vec())
+        .collect();
+    if canonical_parts.len() < field_count {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    canonical_parts.truncate(field_count);
+    if assemble_table(&canonical_parts) != new_data {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .pause_list
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .restriction_list
+        .windows(2)
+        .all(|pair| pair[0].lock_hash < pair[1].lock_hash)
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    // Full freezes belong to `pause_list`, so only a single partial level is accepted per entry
+    if new_pausable_data
+        .restriction_list
+        .iter()
+        .any(|restriction| {
+            (restriction.level != RESTRICTION_BLOCK_SEND
+                && restriction.level != RESTRICTION_BLOCK_RECEIVE)
+                || new_pausable_data
+                    .pause_list
+                    .binary_search(&restriction.lock_hash)
+                    .is_ok()
+        })
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .expiry_list
+        .windows(2)
+        .all(|pair| pair[0].lock_hash < pair[1].lock_hash)
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    // Expiries only make sense for entries of the same cell
+    if new_pausable_data.expiry_list.iter().any(|expiry| {
+        expiry.expiry_epoch == 0
+            || new_pausable_data.restriction_level(&expiry.lock_hash) == RESTRICTION_NONE
+    }) {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .frozen_cells
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .lock_code_hash_rules
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .blocked_type_hashes
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .pending_unpauses
+        .windows(2)
+        .all(|pair| pair[0].lock_hash < pair[1].lock_hash)
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .redemption_lock_hashes
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+    {
+        return Err(Error::NonCanonicalPauseList);
+    }
+    if !new_pausable_data
+        .approvers
+        .windows(2)
+        .all(|pair| pair[0] < pair[1])
+        || new_pausable_data.approval_threshold as usize > new_pausable_data.approvers.len()
+    {
+        return Err(Error::InvalidApprovers);
+    }
+    Ok(new_pausable_data)
+}
+
+/// Follows the chain through cell deps and makes sure it terminates without coming back to the cell being updated.
+fn verify_acyclic_chain(script: &Script, mut next_type_script: Script) -> Result<(), Error> {
+    debug!("Entered verify_acyclic_chain");
+    let mut seen_type_hashes: Vec<Byte32> = vec![script.calc_script_hash()];
+    loop {
+        if seen_type_hashes.contains(&next_type_script.calc_script_hash()) {
+            return Err(Error::CyclicPauseList);
+        }
+        seen_type_hashes.push(next_type_script.calc_script_hash());
+
+        let index = QueryIter::new(load_cell_type, Source::CellDep)
+            .position(|type_script| type_script.as_ref() == Some(&next_type_script))
+            .ok_or(Error::IncompletePauseList)?;
+        let next_pausable_data = decode_registry_data(&load_cell_data(index, Source::CellDep)?)?;
+        match next_pausable_data.next_type_script {
+            Some(ref next_type_script_like) => {
+                next_type_script = script_like_to_script(next_type_script_like)
+            }
+            None => return Ok(()),
+        }
+    }
+}
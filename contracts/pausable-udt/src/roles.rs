@@ -0,0 +1,128 @@
+use crate::config::{ROLES_CELL_TYPE_ARGS, ROLES_CELL_TYPE_CODE_HASH, ROLES_CELL_TYPE_HASH_TYPE};
+use crate::error::Error;
+use crate::registry::{self, ROLES_KIND};
+use crate::utils::{check_owner_mode, parse_config_script};
+use alloc::vec::Vec;
+use ckb_ssri_std::utils::high_level::{find_cell_data_by_out_point, find_out_point_by_type};
+use ckb_ssri_std::utils::should_fallback;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::Script;
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::high_level::{
+    load_cell_data, load_cell_lock_hash, load_cell_type, load_script, QueryIter,
+};
+use serde_molecule::{from_slice, to_vec};
+
+pub const ROLE_ADMIN: u8 = 0;
+pub const ROLE_MINTER: u8 = 1;
+pub const ROLE_PAUSER: u8 = 2;
+pub const ROLE_UNPAUSER: u8 = 3;
+
+pub use crate::cell_data::UDTRolesData;
+
+impl UDTRolesData {
+    pub fn members(&self, role: u8) -> &[[u8; 32]] {
+        self.roles
+            .iter()
+            .find(|role_members| role_members.role == role)
+            .map(|role_members| role_members.lock_hashes.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn has_role(&self, role: u8, lock_hash: &[u8; 32]) -> bool {
+        self.members(role).binary_search(lock_hash).is_ok()
+    }
+
+    /// Whether any input of the current transaction is locked by a member of `role`.
+    pub fn inputs_have_role(&self, role: u8) -> bool {
+        QueryIter::new(load_cell_lock_hash, Source::Input)
+            .any(|lock_hash| self.has_role(role, &lock_hash))
+    }
+}
+
+pub fn get_roles_script() -> Result<Option<Script>, Error> {
+    parse_config_script(
+        ROLES_CELL_TYPE_CODE_HASH,
+        ROLES_CELL_TYPE_HASH_TYPE,
+        ROLES_CELL_TYPE_ARGS,
+    )
+}
+
+pub fn build_roles_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let registry_script = registry::build_registry_script(script, type_id)?;
+    let mut args: Vec<u8> = registry_script.args().unpack();
+    args.push(ROLES_KIND);
+    Ok(registry_script.as_builder().args(args.pack()).build())
+}
+
+/// Loads the roles cell from `CellDep` on-chain or through SSRI otherwise. Returns `None` when roles are not configured.
+pub fn load_roles() -> Result<Option<UDTRolesData>, Error> {
+    debug!("Entered load_roles");
+    let roles_script = match get_roles_script()? {
+        Some(script) => script,
+        None => return Ok(None),
+    };
+    let data = match should_fallback()? {
+        true => {
+            let index = QueryIter::new(load_cell_type, Source::CellDep)
+                .position(|type_script| type_script.as_ref() == Some(&roles_script))
+                .ok_or(Error::MissingRolesCell)?;
+            load_cell_data(index, Source::CellDep)?
+        }
+        false => find_cell_data_by_out_point(find_out_point_by_type(roles_script)?)?,
+    };
+    Ok(Some(from_slice(&data, false)?))
+}
+
+fn load_canonical_roles_data() -> Result<UDTRolesData, Error> {
+    let data = load_cell_data(0, Source::GroupOutput)?;
+    let roles_data: UDTRolesData = from_slice(&data, false).map_err(|_| Error::InvalidRolesData)?;
+    if to_vec(&roles_data, false)? != data {
+        return Err(Error::InvalidRolesData);
+    }
+    if !roles_data
+        .roles
+        .windows(2)
+        .all(|pair| pair[0].role < pair[1].role)
+    {
+        return Err(Error::InvalidRolesData);
+    }
+    if roles_data.roles.iter().any(|role_members| {
+        role_members.role > ROLE_UNPAUSER
+            || !role_members
+                .lock_hashes
+                .windows(2)
+                .all(|pair| pair[0] < pair[1])
+    }) {
+        return Err(Error::InvalidRolesData);
+    }
+    Ok(roles_data)
+}
+
+pub fn verify_roles_update() -> Result<(), Error> {
+    debug!("Entered verify_roles_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = registry::verify_type_id(&script)?;
+
+    if input_count == 0 {
+        debug!("Creating roles cell");
+        if !check_owner_mode(&registry::owner_args(&args)?)? {
+            return Err(Error::NoRolePermission);
+        }
+    } else {
+        let old_roles_data: UDTRolesData =
+            from_slice(&load_cell_data(0, Source::GroupInput)?, false)?;
+        if !old_roles_data.inputs_have_role(ROLE_ADMIN) {
+            return Err(Error::NoRolePermission);
+        }
+    }
+
+    if output_count == 0 {
+        debug!("Destroying roles cell");
+        return Ok(());
+    }
+    load_canonical_roles_data()?;
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use crate::config::{
+    SMT_REGISTRY_CELL_TYPE_ARGS, SMT_REGISTRY_CELL_TYPE_CODE_HASH, SMT_REGISTRY_CELL_TYPE_HASH_TYPE,
+};
+use crate::error::Error;
+use crate::registry::{self, SMT_REGISTRY_KIND};
+use crate::utils::parse_config_script;
+use alloc::vec::Vec;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::Script;
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::error::SysError;
+use ckb_std::high_level::{
+    load_cell_data, load_cell_type, load_script, load_witness_args, QueryIter,
+};
+use serde_molecule::{from_slice, to_vec};
+
+pub use crate::cell_data::{
+    SmtLockProof, SmtLockProofs, SmtProof, SmtUpdate, SmtUpdates, UDTPausableSmtData,
+};
+pub use crate::smt_tree::{compute_root_from_leaves, generate_proof};
+use crate::smt_tree::{get_bit, leaf_hash, merge, SMT_HEIGHT, SMT_ZERO};
+
+pub fn compute_root(
+    lock_hash: &[u8; 32],
+    paused: bool,
+    proof: &SmtProof,
+) -> Result<[u8; 32], Error> {
+    let mut siblings = proof.siblings.iter();
+    let mut current = leaf_hash(lock_hash, paused);
+    for height in (0..SMT_HEIGHT).rev() {
+        let sibling = if get_bit(&proof.bitmap, height) {
+            siblings.next().ok_or(Error::InvalidSmtProof)?
+        } else {
+            &SMT_ZERO
+        };
+        current = if get_bit(lock_hash, height) {
+            merge(sibling, &current)
+        } else {
+            merge(&current, sibling)
+        };
+    }
+    if siblings.next().is_some() {
+        return Err(Error::InvalidSmtProof);
+    }
+    Ok(current)
+}
+
+pub fn get_smt_registry_script() -> Result<Option<Script>, Error> {
+    parse_config_script(
+        SMT_REGISTRY_CELL_TYPE_CODE_HASH,
+        SMT_REGISTRY_CELL_TYPE_HASH_TYPE,
+        SMT_REGISTRY_CELL_TYPE_ARGS,
+    )
+}
+
+pub fn build_smt_registry_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let registry_script = registry::build_registry_script(script, type_id)?;
+    let mut args: Vec<u8> = registry_script.args().unpack();
+    args.push(SMT_REGISTRY_KIND);
+    Ok(registry_script.as_builder().args(args.pack()).build())
+}
+
+fn load_smt_root_from_cell_dep(smt_registry_script: &Script) -> Result<[u8; 32], Error> {
+    let index = QueryIter::new(load_cell_type, Source::CellDep)
+        .position(|type_script| type_script.as_ref() == Some(smt_registry_script))
+        .ok_or(Error::IncompletePauseList)?;
+    let smt_data: UDTPausableSmtData = from_slice(&load_cell_data(index, Source::CellDep)?, false)?;
+    Ok(smt_data.root)
+}
+
+fn load_smt_lock_proofs() -> Result<SmtLockProofs, Error> {
+    let proofs_bytes = match load_witness_args(0, Source::GroupInput) {
+        Ok(witness_args) => witness_args.input_type().to_opt(),
+        Err(SysError::IndexOutOfBound) => load_witness_args(0, Source::GroupOutput)?
+            .output_type()
+            .to_opt(),
+        Err(err) => return Err(err.into()),
+    }
+    .ok_or(Error::InvalidSmtProof)?;
+    from_slice(&proofs_bytes.raw_data(), false).map_err(|_| Error::InvalidSmtProof)
+}
+
+/// Requires a valid non-membership proof for each lock hash. A paused lock hash has none, so the transaction is aborted.
+pub fn verify_not_paused_by_smt(lock_hashes: &[[u8; 32]]) -> Result<(), Error> {
+    debug!("Entered verify_not_paused_by_smt");
+    let smt_registry_script = match get_smt_registry_script()? {
+        Some(script) => script,
+        None => return Ok(()),
+    };
+    let root = load_smt_root_from_cell_dep(&smt_registry_script)?;
+    let smt_lock_proofs = load_smt_lock_proofs()?;
+    for lock_hash in lock_hashes.iter() {
+        let lock_proof = smt_lock_proofs
+            .proofs
+            .iter()
+            .find(|lock_proof| lock_proof.lock_hash == *lock_hash)
+            .ok_or(Error::InvalidSmtProof)?;
+        if compute_root(lock_hash, false, &lock_proof.proof)? != root {
+            debug!("No non-membership proof for lock hash: {:?}", lock_hash);
+            return Err(Error::AbortedFromPause);
+        }
+    }
+    Ok(())
+}
+
+pub fn verify_smt_registry_update() -> Result<(), Error> {
+    debug!("Entered verify_smt_registry_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = registry::verify_type_id(&script)?;
+    if output_count == 0 {
+        debug!("Destroying SMT registry cell");
+        return registry::verify_pause_authority(&args, false, true);
+    }
+
+    let old_root = match input_count {
+        0 => SMT_ZERO,
+        _ => {
+            let old_smt_data: UDTPausableSmtData =
+                from_slice(&load_cell_data(0, Source::GroupInput)?, false)?;
+            old_smt_data.root
+        }
+    };
+    let new_data = load_cell_data(0, Source::GroupOutput)?;
+    let new_smt_data: UDTPausableSmtData =
+        from_slice(&new_data, false).map_err(|_| Error::InvalidPauseData)?;
+    if to_vec(&new_smt_data, false)? != new_data {
+        return Err(Error::NonCanonicalPauseList);
+    }
+
+    let updates_source = match input_count {
+        0 => Source::GroupOutput,
+        _ => Source::GroupInput,
+    };
+    let witness_args = load_witness_args(0, updates_source)?;
+    let updates_bytes = match updates_source {
+        Source::GroupInput => witness_args.input_type().to_opt(),
+        _ => witness_args.output_type().to_opt(),
+    };
+    let smt_updates: SmtUpdates = match updates_bytes {
+        Some(bytes) => from_slice(&bytes.raw_data(), false).map_err(|_| Error::InvalidSmtProof)?,
+        None => SmtUpdates::default(),
+    };
+
+    let tightens = smt_updates.updates.iter().any(|update| update.paused != 0);
+    let loosens = smt_updates.updates.iter().any(|update| update.paused == 0);
+    registry::verify_pause_authority(&args, tightens, loosens)?;
+
+    let mut current_root = old_root;
+    for update in smt_updates.updates.iter() {
+        let paused = update.paused != 0;
+        if compute_root(&update.lock_hash, !paused, &update.proof)? != current_root {
+            return Err(Error::InvalidSmtProof);
+        }
+        current_root = compute_root(&update.lock_hash, paused, &update.proof)?;
+    }
+    if current_root != new_smt_data.root {
+        return Err(Error::InvalidSmtProof);
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+// NOTE: The sparse merkle tree of the SMT registry, shared by on-chain verification and the SSRI builders. This file must not depend on syscalls, since the tests crate includes it as is to generate proofs.
+use crate::cell_data::SmtProof;
+use ckb_hash::new_blake2b;
+
+// NOTE: The SMT registry stores only the root of a 256-level sparse merkle tree keyed by lock hash. Empty subtrees hash to zero so that proofs only need to carry non-zero siblings.
+pub const SMT_ZERO: [u8; 32] = [0u8; 32];
+pub const SMT_PAUSED_VALUE: [u8; 32] = [1u8; 32];
+pub const SMT_HEIGHT: usize = 256;
+
+pub fn get_bit(bytes: &[u8; 32], index: usize) -> bool {
+    (bytes[index / 8] >> (7 - index % 8)) & 1 == 1
+}
+
+pub fn set_bit(bytes: &mut [u8; 32], index: usize) {
+    bytes[index / 8] |= 1 << (7 - index % 8);
+}
+
+pub fn merge(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if *left == SMT_ZERO && *right == SMT_ZERO {
+        return SMT_ZERO;
+    }
+    let mut hasher = new_blake2b();
+    hasher.update(left);
+    hasher.update(right);
+    let mut ret = [0; 32];
+    hasher.finalize(&mut ret);
+    ret
+}
+
+pub fn leaf_hash(lock_hash: &[u8; 32], paused: bool) -> [u8; 32] {
+    if !paused {
+        return SMT_ZERO;
+    }
+    let mut hasher = new_blake2b();
+    hasher.update(lock_hash);
+    hasher.update(&SMT_PAUSED_VALUE);
+    let mut ret = [0; 32];
+    hasher.finalize(&mut ret);
+    ret
+}
+
+/// Root of the subtree holding `leaves`, which must be sorted and share their first `height` bits.
+fn subtree_root(leaves: &[[u8; 32]], height: usize) -> [u8; 32] {
+    if leaves.is_empty() {
+        return SMT_ZERO;
+    }
+    if height == SMT_HEIGHT {
+        return leaf_hash(&leaves[0], true);
+    }
+    let split = leaves.partition_point(|leaf| !get_bit(leaf, height));
+    merge(
+        &subtree_root(&leaves[..split], height + 1),
+        &subtree_root(&leaves[split..], height + 1),
+    )
+}
+
+pub fn compute_root_from_leaves(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut sorted_leaves = leaves.to_vec();
+    sorted_leaves.sort();
+    sorted_leaves.dedup();
+    subtree_root(&sorted_leaves, 0)
+}
+
+// NOTE: Generating proofs hashes the whole tree, so it is only meant for SSRI calls and never for on-chain verification.
+pub fn generate_proof(leaves: &[[u8; 32]], lock_hash: &[u8; 32]) -> SmtProof {
+    let mut sorted_leaves = leaves.to_vec();
+    sorted_leaves.sort();
+    sorted_leaves.dedup();
+
+    let mut proof = SmtProof::default();
+    let mut current_leaves: &[[u8; 32]] = &sorted_leaves;
+    for height in 0..SMT_HEIGHT {
+        let split = current_leaves.partition_point(|leaf| !get_bit(leaf, height));
+        let (left, right) = current_leaves.split_at(split);
+        let (same_side, other_side) = if get_bit(lock_hash, height) {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        let sibling = subtree_root(other_side, height + 1);
+        if sibling != SMT_ZERO {
+            set_bit(&mut proof.bitmap, height);
+            proof.siblings.push(sibling);
+        }
+        current_leaves = same_side;
+    }
+    proof.siblings.reverse();
+    proof
+}
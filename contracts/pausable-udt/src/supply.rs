@@ -0,0 +1,115 @@
+use crate::config::{
+    SUPPLY_CELL_TYPE_ARGS, SUPPLY_CELL_TYPE_CODE_HASH, SUPPLY_CELL_TYPE_HASH_TYPE,
+};
+use crate::error::Error;
+use crate::registry::{self, SUPPLY_KIND};
+use crate::utils::{check_owner_mode, collect_amount_by_type_hash, parse_config_script};
+use alloc::vec::Vec;
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::packed::Script;
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+use ckb_std::high_level::{
+    load_cell_data, load_cell_lock_hash, load_cell_type, load_script, load_script_hash, QueryIter,
+};
+use serde_molecule::{from_slice, to_vec};
+
+pub use crate::cell_data::UDTSupplyData;
+
+pub fn get_supply_script() -> Result<Option<Script>, Error> {
+    parse_config_script(
+        SUPPLY_CELL_TYPE_CODE_HASH,
+        SUPPLY_CELL_TYPE_HASH_TYPE,
+        SUPPLY_CELL_TYPE_ARGS,
+    )
+}
+
+pub fn build_supply_script(script: &Script, type_id: [u8; 32]) -> Result<Script, Error> {
+    let registry_script = registry::build_registry_script(script, type_id)?;
+    let mut args: Vec<u8> = registry_script.args().unpack();
+    args.push(SUPPLY_KIND);
+    Ok(registry_script.as_builder().args(args.pack()).build())
+}
+
+fn load_canonical_supply_data(index: usize, source: Source) -> Result<UDTSupplyData, Error> {
+    let data = load_cell_data(index, source)?;
+    let supply_data: UDTSupplyData =
+        from_slice(&data, false).map_err(|_| Error::InvalidSupplyData)?;
+    if to_vec(&supply_data, false)? != data {
+        return Err(Error::InvalidSupplyData);
+    }
+    Ok(supply_data)
+}
+
+/// Requires the supply cell to be updated by any token transaction that mints or burns, and to stay within its maximum supply.
+pub fn verify_supply_tracked(inputs_amount: u128, outputs_amount: u128) -> Result<(), Error> {
+    debug!("Entered verify_supply_tracked");
+    let supply_script = match get_supply_script()? {
+        Some(script) => script,
+        None => return Ok(()),
+    };
+    if inputs_amount == outputs_amount {
+        return Ok(());
+    }
+    let index = QueryIter::new(load_cell_type, Source::Output)
+        .position(|type_script| type_script.as_ref() == Some(&supply_script))
+        .ok_or(Error::SupplyNotTracked)?;
+    let supply_data = load_canonical_supply_data(index, Source::Output)?;
+    if supply_data.udt_type_hash != load_script_hash()? {
+        return Err(Error::InvalidSupplyData);
+    }
+    if supply_data.total_supply > supply_data.max_supply {
+        return Err(Error::ExceededMaxSupply);
+    }
+    Ok(())
+}
+
+pub fn verify_supply_update() -> Result<(), Error> {
+    debug!("Entered verify_supply_update");
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let (input_count, output_count) = registry::verify_type_id(&script)?;
+
+    if input_count == 0 || output_count == 0 {
+        debug!("Creating or destroying supply cell");
+        if !check_owner_mode(&registry::owner_args(&args)?)? {
+            return Err(Error::NoMintPermission);
+        }
+    }
+    if output_count == 0 {
+        return Ok(());
+    }
+
+    let new_supply_data = load_canonical_supply_data(0, Source::GroupOutput)?;
+    if new_supply_data.total_supply > new_supply_data.max_supply {
+        return Err(Error::ExceededMaxSupply);
+    }
+    if input_count == 0 {
+        return Ok(());
+    }
+
+    let old_supply_data = load_canonical_supply_data(0, Source::GroupInput)?;
+    if old_supply_data.udt_type_hash != new_supply_data.udt_type_hash
+        || old_supply_data.max_supply != new_supply_data.max_supply
+    {
+        return Err(Error::InvalidSupplyData);
+    }
+    // The supply cell uses a lock anyone can unlock, so only owner mode may move it to another lock
+    if load_cell_lock_hash(0, Source::GroupOutput)? != load_cell_lock_hash(0, Source::GroupInput)?
+        && !check_owner_mode(&registry::owner_args(&args)?)?
+    {
+        return Err(Error::InvalidSupplyData);
+    }
+    let inputs_amount = collect_amount_by_type_hash(&new_supply_data.udt_type_hash, Source::Input)?;
+    let outputs_amount =
+        collect_amount_by_type_hash(&new_supply_data.udt_type_hash, Source::Output)?;
+    let expected_total_supply = old_supply_data
+        .total_supply
+        .checked_add(outputs_amount)
+        .and_then(|total_supply| total_supply.checked_sub(inputs_amount))
+        .ok_or(Error::InvalidSupplyData)?;
+    if new_supply_data.total_supply != expected_total_supply {
+        return Err(Error::InvalidSupplyData);
+    }
+    Ok(())
+}
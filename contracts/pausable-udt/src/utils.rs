@@ -1,53 +1,151 @@
+use crate::config::{
+    ExtensionDataFormat, EXTENSION_DATA_FORMAT, FEE_RATE_BPS, RECOVERY_LOCK_ARGS,
+    RECOVERY_LOCK_CODE_HASH, RECOVERY_LOCK_HASH_TYPE, TREASURY_LOCK_ARGS, TREASURY_LOCK_CODE_HASH,
+    TREASURY_LOCK_HASH_TYPE,
+};
 use crate::error::Error;
 use alloc::{ffi::CString, vec::Vec};
-use ckb_ssri_std::public_module_traits::udt::UDT_LEN;
+use ckb_hash::new_blake2b;
+use ckb_ssri_std::public_module_traits::udt::{ScriptLike, UDT_LEN};
 use ckb_std::{
     ckb_constants::Source,
-    ckb_types::bytes::Bytes,
+    ckb_types::{
+        bytes::Bytes,
+        core::ScriptHashType,
+        packed::{Byte, BytesOpt, BytesVec, CellInput, Script, WitnessArgs},
+        prelude::*,
+    },
     debug,
-    high_level::{decode_hex, load_cell_data, load_cell_lock_hash, QueryIter},
+    high_level::{
+        decode_hex, load_cell_data, load_cell_lock_hash, load_cell_type_hash, load_header,
+        load_input_since, QueryIter,
+    },
+    since::{EpochNumberWithFraction, Since},
 };
+use serde_molecule::molecule::disassemble_table;
 
-pub fn collect_inputs_amount() -> Result<u128, Error> {
-    debug!("Entered collect_inputs_amount");
+/// Reads the amount from the first `UDT_LEN` bytes of token cell data and returns it with the extension data that follows.
+pub fn parse_amount(data: &[u8]) -> Result<(u128, &[u8]), Error> {
+    if data.len() < UDT_LEN {
+        return Err(Error::Encoding);
+    }
     let mut buf = [0u8; UDT_LEN];
+    buf.copy_from_slice(&data[..UDT_LEN]);
+    Ok((u128::from_le_bytes(buf), &data[UDT_LEN..]))
+}
+
+pub fn verify_extension_data(extension_data: &[u8]) -> Result<(), Error> {
+    match EXTENSION_DATA_FORMAT {
+        ExtensionDataFormat::Any => Ok(()),
+        ExtensionDataFormat::Forbidden if extension_data.is_empty() => Ok(()),
+        ExtensionDataFormat::Forbidden => Err(Error::InvalidExtensionData),
+        ExtensionDataFormat::MoleculeTable if extension_data.is_empty() => Ok(()),
+        ExtensionDataFormat::MoleculeTable => disassemble_table(extension_data)
+            .map(|_| ())
+            .map_err(|_| Error::InvalidExtensionData),
+    }
+}
+
+pub fn encode_amount(amount: u128, extension_data: &[u8]) -> Vec<u8> {
+    let mut data = amount.to_le_bytes().to_vec();
+    data.extend_from_slice(extension_data);
+    data
+}
 
+pub fn collect_inputs_amount() -> Result<u128, Error> {
+    debug!("Entered collect_inputs_amount");
     let udt_list = QueryIter::new(load_cell_data, Source::GroupInput)
-        .map(|data| {
-            if data.len() == UDT_LEN {
-                buf.copy_from_slice(&data);
-                Ok(u128::from_le_bytes(buf))
-            } else {
-                Err(Error::Encoding)
-            }
-        })
+        .map(|data| parse_amount(&data).map(|(amount, _)| amount))
         .collect::<Result<Vec<_>, Error>>()?;
     Ok(udt_list.into_iter().sum::<u128>())
 }
 
+/// Like `collect_inputs_amount`, and also checks the extension data of every output against `EXTENSION_DATA_FORMAT`.
 pub fn collect_outputs_amount() -> Result<u128, Error> {
     debug!("Entered collect_outputs_amount");
-    let mut buf = [0u8; UDT_LEN];
-
     let udt_list = QueryIter::new(load_cell_data, Source::GroupOutput)
         .map(|data| {
-            if data.len() == UDT_LEN {
-                buf.copy_from_slice(&data);
-                // u128 is 16 bytes
-                Ok(u128::from_le_bytes(buf))
-            } else {
-                Err(Error::Encoding)
-            }
+            let (amount, extension_data) = parse_amount(&data)?;
+            verify_extension_data(extension_data)?;
+            Ok(amount)
         })
         .collect::<Result<Vec<_>, Error>>()?;
     Ok(udt_list.into_iter().sum::<u128>())
 }
 
+/// Returns the token amounts of inputs and outputs under each lock hash of the transaction, as `(lock_hash, inputs_amount, outputs_amount)`.
+pub fn collect_amounts_by_lock() -> Result<Vec<([u8; 32], u128, u128)>, Error> {
+    let mut amounts: Vec<([u8; 32], u128, u128)> = Vec::new();
+    for (source, is_input) in [(Source::GroupInput, true), (Source::GroupOutput, false)] {
+        for (lock_hash, data) in QueryIter::new(load_cell_lock_hash, source)
+            .zip(QueryIter::new(load_cell_data, source))
+        {
+            let amount = parse_amount(&data)?.0;
+            let index = match amounts.iter().position(|entry| entry.0 == lock_hash) {
+                Some(index) => index,
+                None => {
+                    amounts.push((lock_hash, 0, 0));
+                    amounts.len() - 1
+                }
+            };
+            let entry = &mut amounts[index];
+            let total = match is_input {
+                true => &mut entry.1,
+                false => &mut entry.2,
+            };
+            *total = total.checked_add(amount).ok_or(Error::Overflow)?;
+        }
+    }
+    Ok(amounts)
+}
+
+/// Returns the lock that receives transfer fees, or `None` when fees are disabled.
+pub fn get_treasury_lock() -> Result<Option<Script>, Error> {
+    if FEE_RATE_BPS == 0 {
+        return Ok(None);
+    }
+    parse_config_script(
+        TREASURY_LOCK_CODE_HASH,
+        TREASURY_LOCK_HASH_TYPE,
+        TREASURY_LOCK_ARGS,
+    )
+}
+
+/// `FEE_RATE_BPS` basis points of `amount`, rounded down. Split so that it can not overflow.
+pub fn calc_transfer_fee(amount: u128) -> u128 {
+    let fee_rate_bps = FEE_RATE_BPS as u128;
+    amount / 10_000 * fee_rate_bps + amount % 10_000 * fee_rate_bps / 10_000
+}
+
+// NOTE: Same owner mode flags as xUDT. Token args may be <owner hash> <flags: u32 LE>, and plain 32-byte args mean flags 0. Other lengths are rejected so they never overlap the registry args layouts.
+pub const OWNER_MODE_INPUT_TYPE_MASK: u32 = 0x8000_0000;
+pub const OWNER_MODE_OUTPUT_TYPE_MASK: u32 = 0x4000_0000;
+pub const OWNER_MODE_INPUT_LOCK_NOT_MASK: u32 = 0x2000_0000;
+pub const OWNER_MODE_FLAGS_LEN: usize = 4;
+
 pub fn check_owner_mode(args: &Bytes) -> Result<bool, Error> {
     debug!("Entered check_owner_mode");
-    let is_owner_mode = QueryIter::new(load_cell_lock_hash, Source::Input)
-        .find(|lock_hash| args[..] == lock_hash[..])
-        .is_some();
+    let flags = match args.len() {
+        32 => 0,
+        len if len == 32 + OWNER_MODE_FLAGS_LEN => {
+            u32::from_le_bytes(args[32..].try_into().map_err(|_| Error::InvalidTokenArgs)?)
+        }
+        _ => return Err(Error::InvalidTokenArgs),
+    };
+    let owner_hash = &args[..32];
+    debug!("Owner mode flags: {:#010x}", flags);
+
+    let mut is_owner_mode = flags & OWNER_MODE_INPUT_LOCK_NOT_MASK == 0
+        && QueryIter::new(load_cell_lock_hash, Source::Input)
+            .any(|lock_hash| owner_hash == lock_hash);
+    if !is_owner_mode && flags & OWNER_MODE_INPUT_TYPE_MASK != 0 {
+        is_owner_mode = QueryIter::new(load_cell_type_hash, Source::Input)
+            .any(|type_hash| type_hash.map(|hash| owner_hash == hash).unwrap_or(false));
+    }
+    if !is_owner_mode && flags & OWNER_MODE_OUTPUT_TYPE_MASK != 0 {
+        is_owner_mode = QueryIter::new(load_cell_type_hash, Source::Output)
+            .any(|type_hash| type_hash.map(|hash| owner_hash == hash).unwrap_or(false));
+    }
     debug!("Owner mode: {}", is_owner_mode);
     Ok(is_owner_mode)
 }
@@ -65,3 +163,134 @@ pub fn format_pause_list(pause_list_str_vec: Vec<&str>) -> Vec<[u8; 32]> {
     formatted_pause_u8_32_vec
 }
 
+pub fn script_like_to_script(script_like: &ScriptLike) -> Script {
+    Script::new_builder()
+        .code_hash(script_like.code_hash.pack())
+        .hash_type(Byte::new(script_like.hash_type))
+        .args(script_like.args.pack())
+        .build()
+}
+
+/// Calculates the Type ID args the same way the built-in Type ID script does.
+pub fn calc_type_id(first_cell_input: &CellInput, output_index: u64) -> [u8; 32] {
+    let mut hasher = new_blake2b();
+    hasher.update(first_cell_input.as_slice());
+    hasher.update(&output_index.to_le_bytes());
+    let mut ret = [0; 32];
+    hasher.finalize(&mut ret);
+    ret
+}
+
+/// Sets `input_type` (for `Source::Input`) or `output_type` (for `Source::Output`) of the witness at `index`, keeping whatever else the witness already carries.
+pub fn set_witness_args_type(
+    witnesses: BytesVec,
+    index: usize,
+    source: Source,
+    data: Vec<u8>,
+) -> Result<BytesVec, Error> {
+    let mut witness_vec: Vec<ckb_std::ckb_types::packed::Bytes> = witnesses.into_iter().collect();
+    while witness_vec.len() <= index {
+        witness_vec.push(Default::default());
+    }
+    let witness_args = WitnessArgs::from_slice(&witness_vec[index].raw_data())
+        .unwrap_or_default()
+        .as_builder();
+    let type_field = BytesOpt::new_builder().set(Some(data.pack())).build();
+    let witness_args = match source {
+        Source::Input => witness_args.input_type(type_field),
+        Source::Output => witness_args.output_type(type_field),
+        _ => return Err(Error::SSRIMethodsArgsInvalid),
+    }
+    .build();
+    witness_vec[index] = witness_args.as_bytes().pack();
+    Ok(BytesVec::new_builder().set(witness_vec).build())
+}
+
+/// Returns the highest epoch number proven by header deps or absolute epoch `since` of inputs. The current epoch is at least this value.
+pub fn current_epoch_lower_bound() -> Result<Option<u64>, Error> {
+    let header_epochs = QueryIter::new(load_header, Source::HeaderDep).map(|header| {
+        EpochNumberWithFraction::from_full_value(header.raw().epoch().unpack()).number()
+    });
+    let since_epochs = QueryIter::new(load_input_since, Source::Input)
+        .map(Since::new)
+        .filter(|since| since.is_absolute())
+        .filter_map(|since| since.extract_lock_value())
+        .filter_map(|lock_value| lock_value.epoch())
+        .map(|epoch| epoch.number());
+    Ok(header_epochs.chain(since_epochs).max())
+}
+
+/// Returns how many epochs the input at `index` has existed for at least, proven by a relative epoch `since` or by the header of the block that created it together with `current_epoch`.
+pub fn input_age_epochs(
+    index: usize,
+    source: Source,
+    current_epoch: Option<u64>,
+) -> Result<u64, Error> {
+    let since = Since::new(load_input_since(index, source)?);
+    let since_age = match since.is_relative() {
+        true => since
+            .extract_lock_value()
+            .and_then(|lock_value| lock_value.epoch())
+            .map(|epoch| epoch.number()),
+        false => None,
+    };
+    let header_age = match (load_header(index, source), current_epoch) {
+        (Ok(header), Some(epoch)) => Some(epoch.saturating_sub(
+            EpochNumberWithFraction::from_full_value(header.raw().epoch().unpack()).number(),
+        )),
+        _ => None,
+    };
+    Ok(since_age.max(header_age).unwrap_or_default())
+}
+
+/// Returns the lock that clawbacks send funds to, or `None` when clawback is disabled.
+pub fn get_recovery_lock() -> Result<Option<Script>, Error> {
+    parse_config_script(
+        RECOVERY_LOCK_CODE_HASH,
+        RECOVERY_LOCK_HASH_TYPE,
+        RECOVERY_LOCK_ARGS,
+    )
+}
+
+/// Builds a script from the hex strings of `config.rs`, or returns `None` when they are left empty.
+pub fn parse_config_script(
+    code_hash: &str,
+    hash_type: ScriptHashType,
+    args: &str,
+) -> Result<Option<Script>, Error> {
+    if code_hash.is_empty() || args.is_empty() {
+        return Ok(None);
+    }
+    let code_hash: [u8; 32] = decode_hex(
+        &CString::new(code_hash)
+            .map_err(|_| Error::InvalidPauseData)?
+            .as_c_str()[2..],
+    )?
+    .try_into()
+    .map_err(|_| Error::InvalidPauseData)?;
+    let args = decode_hex(
+        &CString::new(args)
+            .map_err(|_| Error::InvalidPauseData)?
+            .as_c_str()[2..],
+    )?;
+    Ok(Some(
+        Script::new_builder()
+            .code_hash(code_hash.pack())
+            .hash_type(Byte::new(hash_type.into()))
+            .args(args.pack())
+            .build(),
+    ))
+}
+
+/// Sums the token amounts of the cells typed by `type_hash` in `source`.
+pub fn collect_amount_by_type_hash(type_hash: &[u8; 32], source: Source) -> Result<u128, Error> {
+    let mut amount: u128 = 0;
+    for (index, cell_type_hash) in QueryIter::new(load_cell_type_hash, source).enumerate() {
+        if cell_type_hash.as_ref() != Some(type_hash) {
+            continue;
+        }
+        let (cell_amount, _) = parse_amount(&load_cell_data(index, source)?)?;
+        amount = amount.checked_add(cell_amount).ok_or(Error::Overflow)?;
+    }
+    Ok(amount)
+}
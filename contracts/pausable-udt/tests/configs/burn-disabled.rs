@@ -0,0 +1 @@
+pub const BURN_POLICY: BurnPolicy = BurnPolicy::Disabled;
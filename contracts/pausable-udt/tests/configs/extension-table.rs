@@ -0,0 +1 @@
+pub const EXTENSION_DATA_FORMAT: ExtensionDataFormat = ExtensionDataFormat::MoleculeTable;
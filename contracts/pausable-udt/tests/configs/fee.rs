@@ -0,0 +1,8 @@
+// Always-success script of ckb-testtool with hash type Data1, and the ASCII of "treasury" as args
+use ckb_std::ckb_types::core::ScriptHashType;
+
+pub const TREASURY_LOCK_CODE_HASH: &str =
+    "0xe683b04139344768348499c23eb1326d5a52d6db006c0d2fece00a831f3660d7";
+pub const TREASURY_LOCK_HASH_TYPE: ScriptHashType = ScriptHashType::Data1;
+pub const TREASURY_LOCK_ARGS: &str = "0x7472656173757279";
+pub const FEE_RATE_BPS: u16 = 100;
@@ -0,0 +1,5 @@
+pub const MAX_TRANSFER_AMOUNT: u128 = 1000;
+// Lock hash of the always-success script of ckb-testtool with hash type Data1 and the ASCII of "watched" as args
+pub const WATCH_LIST_LOCK_HASHES: &[&str] =
+    &["0xbc7b1aa8729beb9d5c72e6c6525239097925cb3fd5ebe7955a0666aca4366441"];
+pub const WATCH_LIST_MAX_TRANSFER_AMOUNT: u128 = 100;
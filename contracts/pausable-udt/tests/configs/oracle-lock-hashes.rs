@@ -0,0 +1,4 @@
+// Public key of the oracle private key [0x42; 32] of the tests crate
+pub const COMPLIANCE_ORACLE_PUBKEY: &str =
+    "0x0324653eac434488002cc06bbfb7f10fe18991e35f9fe4302dbea6d2353dc0ab1c";
+pub const ORACLE_ATTESTATION_SCOPE: OracleAttestationScope = OracleAttestationScope::LockHashes;
@@ -0,0 +1,7 @@
+// Always-success script of ckb-testtool with hash type Data1, and the ASCII of "smt registry" as args
+use ckb_std::ckb_types::core::ScriptHashType;
+
+pub const SMT_REGISTRY_CELL_TYPE_CODE_HASH: &str =
+    "0xe683b04139344768348499c23eb1326d5a52d6db006c0d2fece00a831f3660d7";
+pub const SMT_REGISTRY_CELL_TYPE_HASH_TYPE: ScriptHashType = ScriptHashType::Data1;
+pub const SMT_REGISTRY_CELL_TYPE_ARGS: &str = "0x736d74207265676973747279";
@@ -0,0 +1 @@
+pub const UNPAUSE_DELAY_EPOCHS: u64 = 2;
@@ -11,11 +11,18 @@ use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+extern crate alloc;
+
+#[path = "../../src/cell_data.rs"]
+pub mod cell_data;
+#[path = "../../src/smt_tree.rs"]
+pub mod smt_tree;
 #[cfg(test)]
 mod ssri;
 mod utils;
 mod tests;
 mod tool;
+mod proxy_lock;
 
 // The exact same Loader code from capsule's template, except that
 // now we use MODE as the environment variable
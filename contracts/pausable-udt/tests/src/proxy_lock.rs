@@ -0,0 +1,229 @@
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use serde_molecule::to_vec;
+
+use crate::cell_data::PausableRegistryData;
+use crate::utils::{assert_script_error, build_proxy_lock_test_context, calc_type_id};
+use crate::verify_and_dump_failed_tx;
+
+const MAX_CYCLES: u64 = 10_000_000;
+
+// Error codes of proxy-lock and pausable-udt
+const ERROR_PROXY_CELL_NOT_FOUND: i8 = 9;
+const ERROR_NO_PAUSE_PERMISSION: i8 = 37;
+
+fn registry_data(pause_list: Vec<[u8; 32]>) -> Bytes {
+    let data = PausableRegistryData {
+        pause_list,
+        ..Default::default()
+    };
+    Bytes::from(to_vec(&data, false).expect("serialize"))
+}
+
+#[test]
+pub fn test_create_pause_list_cell_through_proxy_lock() {
+    let mut test_context = build_proxy_lock_test_context();
+    let proxy_cell_out_point = test_context.create_proxy_cell();
+    let capacity_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(2000u64.pack())
+            .lock(test_context.proxy_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+
+    let first_input = CellInput::new_builder()
+        .previous_output(capacity_out_point)
+        .build();
+    let registry_type_script =
+        test_context.build_registry_type_script(calc_type_id(&first_input, 0));
+    let paused_lock_hash: [u8; 32] = test_context
+        .paused_user_lock_script
+        .calc_script_hash()
+        .unpack();
+
+    let tx = TransactionBuilder::default()
+        .inputs(vec![
+            first_input,
+            CellInput::new_builder()
+                .previous_output(proxy_cell_out_point)
+                .build(),
+        ])
+        .outputs(vec![
+            test_context.pause_list_cell_output(&registry_type_script),
+            test_context.proxy_cell_output(),
+        ])
+        .outputs_data(vec![registry_data(vec![paused_lock_hash]), Bytes::default()].pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+
+    let cycles = verify_and_dump_failed_tx(&test_context.context, &tx, MAX_CYCLES)
+        .expect("Create Pause List Cell Tx Failed");
+    println!("Create Pause List Cell Tx cycles: {}", cycles);
+}
+
+#[test]
+pub fn test_pause_through_proxy_lock() {
+    let mut test_context = build_proxy_lock_test_context();
+    let proxy_cell_out_point = test_context.create_proxy_cell();
+    let (pause_list_out_point, registry_type_script) = test_context.create_pause_list_cell(vec![]);
+    let paused_lock_hash: [u8; 32] = test_context
+        .paused_user_lock_script
+        .calc_script_hash()
+        .unpack();
+
+    let tx = TransactionBuilder::default()
+        .inputs(vec![
+            CellInput::new_builder()
+                .previous_output(pause_list_out_point)
+                .build(),
+            CellInput::new_builder()
+                .previous_output(proxy_cell_out_point)
+                .build(),
+        ])
+        .outputs(vec![
+            test_context.pause_list_cell_output(&registry_type_script),
+            test_context.proxy_cell_output(),
+        ])
+        .outputs_data(vec![registry_data(vec![paused_lock_hash]), Bytes::default()].pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+
+    let cycles =
+        verify_and_dump_failed_tx(&test_context.context, &tx, MAX_CYCLES).expect("Pause Tx Failed");
+    println!("Pause Tx cycles: {}", cycles);
+}
+
+#[test]
+pub fn test_unpause_through_proxy_lock() {
+    let mut test_context = build_proxy_lock_test_context();
+    let proxy_cell_out_point = test_context.create_proxy_cell();
+    let paused_lock_hash: [u8; 32] = test_context
+        .paused_user_lock_script
+        .calc_script_hash()
+        .unpack();
+    let (pause_list_out_point, registry_type_script) =
+        test_context.create_pause_list_cell(vec![paused_lock_hash]);
+
+    let tx = TransactionBuilder::default()
+        .inputs(vec![
+            CellInput::new_builder()
+                .previous_output(pause_list_out_point)
+                .build(),
+            CellInput::new_builder()
+                .previous_output(proxy_cell_out_point)
+                .build(),
+        ])
+        .outputs(vec![
+            test_context.pause_list_cell_output(&registry_type_script),
+            test_context.proxy_cell_output(),
+        ])
+        .outputs_data(vec![registry_data(vec![]), Bytes::default()].pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+
+    let cycles = verify_and_dump_failed_tx(&test_context.context, &tx, MAX_CYCLES)
+        .expect("Unpause Tx Failed");
+    println!("Unpause Tx cycles: {}", cycles);
+}
+
+#[test]
+pub fn test_pause_without_proxy_cell() {
+    let mut test_context = build_proxy_lock_test_context();
+    let (pause_list_out_point, registry_type_script) = test_context.create_pause_list_cell(vec![]);
+    let paused_lock_hash: [u8; 32] = test_context
+        .paused_user_lock_script
+        .calc_script_hash()
+        .unpack();
+
+    let tx = TransactionBuilder::default()
+        .inputs(vec![CellInput::new_builder()
+            .previous_output(pause_list_out_point)
+            .build()])
+        .outputs(vec![
+            test_context.pause_list_cell_output(&registry_type_script)
+        ])
+        .outputs_data(vec![registry_data(vec![paused_lock_hash])].pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+
+    let err = test_context.context.verify_tx(&tx, MAX_CYCLES).unwrap_err();
+    assert_script_error(err, ERROR_PROXY_CELL_NOT_FOUND);
+}
+
+#[test]
+pub fn test_pause_without_owner_mode() {
+    let mut test_context = build_proxy_lock_test_context();
+    let proxy_cell_out_point = test_context.create_proxy_cell();
+    // A pause list cell that is not under the proxy lock can be spent, but the registry still wants the owner among the inputs
+    let registry_type_script = test_context.build_registry_type_script([7u8; 32]);
+    let pause_list_output = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(test_context.normal_user_lock_script.clone())
+        .type_(Some(registry_type_script).pack())
+        .build();
+    let pause_list_out_point = test_context
+        .context
+        .create_cell(pause_list_output.clone(), registry_data(vec![]));
+    let paused_lock_hash: [u8; 32] = test_context
+        .paused_user_lock_script
+        .calc_script_hash()
+        .unpack();
+
+    let tx = TransactionBuilder::default()
+        .inputs(vec![
+            CellInput::new_builder()
+                .previous_output(pause_list_out_point)
+                .build(),
+            CellInput::new_builder()
+                .previous_output(proxy_cell_out_point)
+                .build(),
+        ])
+        .outputs(vec![pause_list_output, test_context.proxy_cell_output()])
+        .outputs_data(vec![registry_data(vec![paused_lock_hash]), Bytes::default()].pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+
+    let err = test_context.context.verify_tx(&tx, MAX_CYCLES).unwrap_err();
+    assert_script_error(err, ERROR_NO_PAUSE_PERMISSION);
+}
+
+#[test]
+pub fn test_mint_through_proxy_lock() {
+    let mut test_context = build_proxy_lock_test_context();
+    let proxy_cell_out_point = test_context.create_proxy_cell();
+    let capacity_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(2000u64.pack())
+            .lock(test_context.proxy_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let mint_amount: Uint128 = 10000000000u128.pack();
+
+    let tx = TransactionBuilder::default()
+        .inputs(vec![
+            CellInput::new_builder()
+                .previous_output(capacity_out_point)
+                .build(),
+            CellInput::new_builder()
+                .previous_output(proxy_cell_out_point)
+                .build(),
+        ])
+        .outputs(vec![
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(test_context.normal_user_lock_script.clone())
+                .type_(Some(test_context.pausable_udt_type_script.clone()).pack())
+                .build(),
+            test_context.proxy_cell_output(),
+        ])
+        .outputs_data(vec![mint_amount.as_bytes(), Bytes::default()].pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+
+    let cycles =
+        verify_and_dump_failed_tx(&test_context.context, &tx, MAX_CYCLES).expect("Mint Tx Failed");
+    println!("Mint Tx cycles: {}", cycles);
+}
+
+// NOTE: Transfers from or to paused locks are aborted once the pause list cell is referenced from `config.rs` and included in `CellDep`. The Type ID of that cell is only known after deployment, so this part of the flow is tested against a deployed binary with ckb_ssri_cli.
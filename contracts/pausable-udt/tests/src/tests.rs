@@ -5,11 +5,67 @@ use ckb_std::{
 };
 use ckb_testtool::{
     builtin::ALWAYS_SUCCESS,
-    ckb_types::{core::TransactionBuilder, packed::*, prelude::*},
+    ckb_crypto::secp::Privkey,
+    ckb_types::{
+        core::{EpochNumberWithFraction, HeaderBuilder, TransactionBuilder, TransactionView},
+        packed::*,
+        prelude::*,
+        H256,
+    },
     context::Context,
 };
 
-use crate::utils::build_test_context;
+use ckb_ssri_std::public_module_traits::udt::{ScriptLike, UDTPausableData};
+use serde_molecule::to_vec;
+
+use crate::cell_data::{
+    FrozenCell, GlobalPause, LockCodeHashRule, MinterQuota, PausableRegistryData, PauseExpiry,
+    PauseRestriction, PendingUnpause, RoleMembers, SmtLockProof, SmtLockProofs, SmtUpdate,
+    SmtUpdates, UDTAllowlistData, UDTPausableSmtData, UDTPauseProposalData, UDTQuotaData,
+    UDTRolesData, UDTSupplyData,
+};
+use crate::smt_tree::{compute_root_from_leaves, generate_proof};
+use crate::utils::{
+    assert_script_error, build_test_context, build_test_context_with_binary, calc_type_id,
+    PausableUDTTestContext,
+};
+
+// Error codes of pausable-udt
+const ERROR_INSUFFICIENT_BALANCE: i8 = 32;
+const ERROR_NO_BURN_PERMISSION: i8 = 35;
+const ERROR_NO_PAUSE_PERMISSION: i8 = 37;
+const ERROR_ABORTED_FROM_PAUSE: i8 = 39;
+const ERROR_CYCLIC_PAUSE_LIST: i8 = 41;
+const ERROR_NON_CANONICAL_PAUSE_LIST: i8 = 45;
+const ERROR_INVALID_SMT_PROOF: i8 = 46;
+const ERROR_ABORTED_FROM_GLOBAL_PAUSE: i8 = 47;
+const ERROR_SUPPLY_NOT_TRACKED: i8 = 48;
+const ERROR_INVALID_SUPPLY_DATA: i8 = 49;
+const ERROR_INVALID_EXTENSION_DATA: i8 = 54;
+const ERROR_ABORTED_FROM_FROZEN_CELL: i8 = 55;
+const ERROR_ABORTED_FROM_ALLOWLIST: i8 = 56;
+const ERROR_EXCEEDED_TRANSFER_LIMIT: i8 = 61;
+const ERROR_INSUFFICIENT_TRANSFER_FEE: i8 = 62;
+const ERROR_QUOTA_NOT_TRACKED: i8 = 63;
+const ERROR_EXCEEDED_MINT_QUOTA: i8 = 65;
+const ERROR_ABORTED_FROM_BLOCKED_TYPE: i8 = 66;
+const ERROR_UNPAUSE_NOT_SCHEDULED: i8 = 67;
+const ERROR_UNPAUSE_NOT_DUE: i8 = 68;
+const ERROR_NOT_ENOUGH_APPROVALS: i8 = 69;
+const ERROR_INVALID_APPROVAL: i8 = 72;
+const ERROR_PROPOSAL_NOT_APPROVED: i8 = 73;
+const ERROR_MISSING_ORACLE_ATTESTATION: i8 = 75;
+const ERROR_INVALID_ORACLE_ATTESTATION: i8 = 76;
+const ERROR_INVALID_TOKEN_ARGS: i8 = 78;
+
+// Kind bytes of registry cell args
+const SMT_REGISTRY_KIND: u8 = 1;
+const SUPPLY_KIND: u8 = 2;
+const QUOTA_KIND: u8 = 5;
+const PROPOSAL_KIND: u8 = 6;
+
+const RESTRICTION_BLOCK_SEND: u8 = 0b01;
+const OWNER_MODE_INPUT_TYPE_MASK: u32 = 0x8000_0000;
 
 #[test]
 pub fn test_transfer() {
@@ -203,3 +259,1753 @@ pub fn test_mint() {
         unauthorized_mint_err
     );
 }
+
+#[test]
+pub fn test_pause_list_cell_validation() {
+    let mut test_context = build_test_context();
+
+    let admin_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let admin_input = CellInput::new_builder()
+        .previous_output(admin_out_point.clone())
+        .build();
+
+    let mut registry_args = test_context
+        .admin_lock_script
+        .calc_script_hash()
+        .as_slice()
+        .to_vec();
+    registry_args.extend_from_slice(&calc_type_id(&admin_input, 0));
+    let registry_type_script = test_context
+        .context
+        .build_script(&test_context.pausable_udt_out_point, Bytes::from(registry_args))
+        .expect("script");
+    let registry_output = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(test_context.admin_lock_script.clone())
+        .type_(Some(registry_type_script.clone()).pack())
+        .build();
+
+    let paused_lock_hash: [u8; 32] = test_context
+        .paused_user_lock_script
+        .calc_script_hash()
+        .unpack();
+    let canonical_data = UDTPausableData {
+        pause_list: vec![paused_lock_hash],
+        next_type_script: None,
+    };
+    let create_registry_tx = TransactionBuilder::default()
+        .input(admin_input.clone())
+        .output(registry_output.clone())
+        .output_data(to_vec(&canonical_data, false).unwrap().pack())
+        .cell_deps(vec![test_context.pausable_udt_dep.clone(), test_context.always_success_dep.clone()])
+        .build();
+    let create_registry_tx = test_context.context.complete_tx(create_registry_tx);
+    test_context
+        .context
+        .verify_tx(&create_registry_tx, u64::MAX)
+        .expect("Create Pause List Cell Tx Failed");
+
+    let duplicated_data = UDTPausableData {
+        pause_list: vec![paused_lock_hash, paused_lock_hash],
+        next_type_script: None,
+    };
+    let duplicated_registry_tx = create_registry_tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![to_vec(&duplicated_data, false).unwrap().pack()])
+        .build();
+    let duplicated_registry_err = test_context
+        .context
+        .verify_tx(&duplicated_registry_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(duplicated_registry_err, ERROR_NON_CANONICAL_PAUSE_LIST);
+
+    let self_referencing_data = UDTPausableData {
+        pause_list: vec![paused_lock_hash],
+        next_type_script: Some(ScriptLike {
+            code_hash: registry_type_script.code_hash().unpack(),
+            hash_type: registry_type_script.hash_type().into(),
+            args: registry_type_script.args().raw_data().to_vec(),
+        }),
+    };
+    let self_referencing_registry_tx = create_registry_tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![to_vec(&self_referencing_data, false).unwrap().pack()])
+        .build();
+    let self_referencing_registry_err = test_context
+        .context
+        .verify_tx(&self_referencing_registry_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(self_referencing_registry_err, ERROR_CYCLIC_PAUSE_LIST);
+}
+
+// Local oracle keypair, whose public key is set by `configs/oracle.rs`
+const ORACLE_PRIVKEY: [u8; 32] = [0x42u8; 32];
+
+fn sign_attestation(privkey: &Privkey, message: [u8; 32]) -> Bytes {
+    let signature = privkey
+        .sign_recoverable(&H256::from(message))
+        .unwrap()
+        .serialize();
+    Bytes::from(signature[..64].to_vec())
+}
+
+fn with_attestation(tx: &TransactionView, attestation: Option<Bytes>) -> TransactionView {
+    tx.as_advanced_builder()
+        .set_witnesses(vec![WitnessArgs::new_builder()
+            .output_type(attestation.pack())
+            .build()
+            .as_bytes()
+            .pack()])
+        .build()
+}
+
+fn build_oracle_transfer_tx(test_context: &mut PausableUDTTestContext) -> TransactionView {
+    let wallet_amount: Uint128 = 20000000000u128.pack();
+    let udt_input_outpoint = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(test_context.normal_user_a_lock_script.clone())
+            .type_(Some(test_context.pausable_udt_type_script.clone()).pack())
+            .build(),
+        wallet_amount.as_bytes(),
+    );
+    let udt_output = CellOutput::new_builder()
+        .capacity(100u64.pack())
+        .lock(test_context.normal_user_b_lock_script.clone())
+        .type_(Some(test_context.pausable_udt_type_script.clone()).pack())
+        .build();
+    TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(udt_input_outpoint)
+                .build(),
+        )
+        .output(udt_output)
+        .output_data(wallet_amount.as_bytes().pack())
+        .cell_deps(vec![test_context.pausable_udt_dep.clone(), test_context.always_success_dep.clone()])
+        .build()
+}
+
+#[test]
+pub fn test_compliance_oracle_attestation() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-oracle");
+    let oracle_privkey = Privkey::from_slice(&ORACLE_PRIVKEY);
+    let other_privkey = Privkey::from_slice(&[0x43u8; 32]);
+    let transfer_tx = build_oracle_transfer_tx(&mut test_context);
+
+    // The oracle signs the transaction hash, which does not cover witnesses
+    let message: [u8; 32] = transfer_tx.hash().unpack();
+    let attested_cycles = test_context
+        .context
+        .verify_tx(
+            &with_attestation(&transfer_tx, Some(sign_attestation(&oracle_privkey, message))),
+            u64::MAX,
+        )
+        .expect("Attested Transfer Tx Failed");
+    println!("Attested Transfer Tx cycles: {}", attested_cycles);
+
+    let missing_attestation_err = test_context
+        .context
+        .verify_tx(&with_attestation(&transfer_tx, None), u64::MAX)
+        .unwrap_err();
+    assert_script_error(missing_attestation_err, ERROR_MISSING_ORACLE_ATTESTATION);
+
+    let foreign_attestation_err = test_context
+        .context
+        .verify_tx(
+            &with_attestation(&transfer_tx, Some(sign_attestation(&other_privkey, message))),
+            u64::MAX,
+        )
+        .unwrap_err();
+    assert_script_error(foreign_attestation_err, ERROR_INVALID_ORACLE_ATTESTATION);
+}
+
+#[test]
+pub fn test_compliance_oracle_attestation_without_token_input() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-oracle");
+    let oracle_privkey = Privkey::from_slice(&ORACLE_PRIVKEY);
+    let capacity_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.normal_user_a_lock_script.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let mint_amount: Uint128 = 10000000000u128.pack();
+    let mint_tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(capacity_out_point)
+                .build(),
+        )
+        .output(
+            CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(test_context.normal_user_a_lock_script.clone())
+                .type_(Some(test_context.pausable_udt_type_script.clone()).pack())
+                .build(),
+        )
+        .output_data(mint_amount.as_bytes().pack())
+        .cell_deps(vec![test_context.pausable_udt_dep.clone(), test_context.always_success_dep.clone()])
+        .build();
+
+    let missing_attestation_err = test_context
+        .context
+        .verify_tx(&with_attestation(&mint_tx, None), u64::MAX)
+        .unwrap_err();
+    assert_script_error(missing_attestation_err, ERROR_MISSING_ORACLE_ATTESTATION);
+
+    // The attestation is read from the witness of the first token output, and the mint then fails on its own
+    let message: [u8; 32] = mint_tx.hash().unpack();
+    let unauthorized_mint_err = test_context
+        .context
+        .verify_tx(
+            &with_attestation(&mint_tx, Some(sign_attestation(&oracle_privkey, message))),
+            u64::MAX,
+        )
+        .unwrap_err();
+    assert_script_error(unauthorized_mint_err, ERROR_INSUFFICIENT_BALANCE);
+}
+
+#[test]
+pub fn test_compliance_oracle_lock_hashes_attestation() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-oracle-lock-hashes");
+    let oracle_privkey = Privkey::from_slice(&ORACLE_PRIVKEY);
+    let transfer_tx = build_oracle_transfer_tx(&mut test_context);
+    let lock_hashes_message = |tx: &TransactionView| -> [u8; 32] {
+        let mut lock_hashes: Vec<[u8; 32]> = vec![
+            test_context
+                .normal_user_a_lock_script
+                .calc_script_hash()
+                .unpack(),
+            test_context
+                .normal_user_b_lock_script
+                .calc_script_hash()
+                .unpack(),
+        ];
+        lock_hashes.sort();
+        let mut hasher = ckb_hash::new_blake2b();
+        hasher.update(test_context.pausable_udt_type_script.calc_script_hash().as_slice());
+        hasher.update(tx.inputs().get(0).unwrap().previous_output().as_slice());
+        for lock_hash in lock_hashes.iter() {
+            hasher.update(lock_hash);
+        }
+        let mut message = [0u8; 32];
+        hasher.finalize(&mut message);
+        message
+    };
+
+    // The screening covers the parties and the first input, so the rest of the transaction may still change
+    let attestation = sign_attestation(&oracle_privkey, lock_hashes_message(&transfer_tx));
+    let changed_tx = transfer_tx
+        .as_advanced_builder()
+        .set_outputs(vec![transfer_tx
+            .output(0)
+            .unwrap()
+            .as_builder()
+            .capacity(99u64.pack())
+            .build()])
+        .build();
+    let attested_cycles = test_context
+        .context
+        .verify_tx(&with_attestation(&changed_tx, Some(attestation.clone())), u64::MAX)
+        .expect("Attested Transfer Tx Failed");
+    println!("Attested Transfer Tx cycles: {}", attested_cycles);
+
+    // The same attestation can not be replayed by a transfer between the same parties spending another cell
+    let replayed_tx = build_oracle_transfer_tx(&mut test_context);
+    let replayed_attestation_err = test_context
+        .context
+        .verify_tx(&with_attestation(&replayed_tx, Some(attestation)), u64::MAX)
+        .unwrap_err();
+    assert_script_error(replayed_attestation_err, ERROR_INVALID_ORACLE_ATTESTATION);
+}
+
+fn lock_hash(script: &Script) -> [u8; 32] {
+    script.calc_script_hash().unpack()
+}
+
+fn udt_data(amount: u128) -> Bytes {
+    Bytes::from(amount.to_le_bytes().to_vec())
+}
+
+fn absolute_epoch_since(epoch: u64) -> u64 {
+    0x2000_0000_0000_0000 | EpochNumberWithFraction::new(epoch, 0, 1).full_value()
+}
+
+fn build_udt_tx(
+    test_context: &PausableUDTTestContext,
+    inputs: Vec<CellInput>,
+    outputs: Vec<(&Script, u128)>,
+) -> TransactionView {
+    TransactionBuilder::default()
+        .inputs(inputs)
+        .outputs(
+            outputs
+                .iter()
+                .map(|(lock, _)| test_context.udt_output(lock)),
+        )
+        .outputs_data(outputs.iter().map(|(_, amount)| udt_data(*amount).pack()))
+        .cell_deps(test_context.cell_deps())
+        .build()
+}
+
+/// Adds the first pause list cell of the chain configured by `configs/registry.rs`.
+fn with_pause_list(
+    test_context: &mut PausableUDTTestContext,
+    tx: &TransactionView,
+    pausable_data: &PausableRegistryData,
+) -> TransactionView {
+    let pause_list_type_script = test_context.build_fixed_script("pause list");
+    let pause_list_dep = test_context.create_cell_dep(
+        &pause_list_type_script,
+        Bytes::from(to_vec(pausable_data, false).unwrap()),
+    );
+    tx.as_advanced_builder().cell_dep(pause_list_dep).build()
+}
+
+fn registry_output(test_context: &mut PausableUDTTestContext, type_script: &Script) -> CellOutput {
+    CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(test_context.build_fixed_script("registry"))
+        .type_(Some(type_script.clone()).pack())
+        .build()
+}
+
+fn create_registry_input(
+    test_context: &mut PausableUDTTestContext,
+    type_script: &Script,
+    data: Bytes,
+) -> CellInput {
+    let output = registry_output(test_context, type_script);
+    let out_point = test_context.context.create_cell(output, data);
+    CellInput::new_builder().previous_output(out_point).build()
+}
+
+/// Builds a transaction updating the pause list cell of `type_script` from `old` to `new`, with `inputs` as authorization.
+fn build_registry_update_tx(
+    test_context: &mut PausableUDTTestContext,
+    type_script: &Script,
+    old: &PausableRegistryData,
+    new: &PausableRegistryData,
+    inputs: Vec<CellInput>,
+) -> TransactionView {
+    let registry_input = create_registry_input(
+        test_context,
+        type_script,
+        Bytes::from(to_vec(old, false).unwrap()),
+    );
+    TransactionBuilder::default()
+        .input(registry_input)
+        .inputs(inputs)
+        .output(registry_output(test_context, type_script))
+        .output_data(Bytes::from(to_vec(new, false).unwrap()).pack())
+        .cell_deps(test_context.cell_deps())
+        .build()
+}
+
+/// Commits the first input of `tx` in a block of `epoch`, and adds that block to the header deps.
+fn with_first_input_header(
+    test_context: &mut PausableUDTTestContext,
+    tx: &TransactionView,
+    epoch: u64,
+) -> TransactionView {
+    let header = HeaderBuilder::default()
+        .epoch(
+            EpochNumberWithFraction::new(epoch, 0, 1)
+                .full_value()
+                .pack(),
+        )
+        .build();
+    test_context.context.insert_header(header.clone());
+    let out_point = tx.inputs().get(0).unwrap().previous_output();
+    test_context
+        .context
+        .link_cell_with_block(out_point, header.hash(), 0);
+    tx.as_advanced_builder().header_dep(header.hash()).build()
+}
+
+/// Attaches non-membership proofs for `locks` to the token witness, the way `UDT.attach_smt_proofs` does.
+fn with_smt_lock_proofs(
+    tx: &TransactionView,
+    paused_lock_hashes: &[[u8; 32]],
+    locks: &[&Script],
+) -> TransactionView {
+    let mut lock_hashes: Vec<[u8; 32]> = locks.iter().map(|lock| lock_hash(lock)).collect();
+    lock_hashes.sort();
+    let smt_lock_proofs = SmtLockProofs {
+        proofs: lock_hashes
+            .iter()
+            .map(|lock_hash| SmtLockProof {
+                lock_hash: *lock_hash,
+                proof: generate_proof(paused_lock_hashes, lock_hash),
+            })
+            .collect(),
+    };
+    tx.as_advanced_builder()
+        .set_witnesses(vec![WitnessArgs::new_builder()
+            .input_type(Some(Bytes::from(to_vec(&smt_lock_proofs, false).unwrap())).pack())
+            .build()
+            .as_bytes()
+            .pack()])
+        .build()
+}
+
+#[test]
+pub fn test_smt_non_membership_proofs() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-smt");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let paused_user = test_context.paused_user_lock_script.clone();
+    let paused_lock_hashes = vec![lock_hash(&paused_user)];
+    let smt_registry_type_script = test_context.build_fixed_script("smt registry");
+    let smt_registry_dep = test_context.create_cell_dep(
+        &smt_registry_type_script,
+        Bytes::from(
+            to_vec(
+                &UDTPausableSmtData {
+                    root: compute_root_from_leaves(&paused_lock_hashes),
+                },
+                false,
+            )
+            .unwrap(),
+        ),
+    );
+
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input.clone()], vec![(&user_b, 100)])
+        .as_advanced_builder()
+        .cell_dep(smt_registry_dep.clone())
+        .build();
+    let proven_tx = with_smt_lock_proofs(&transfer_tx, &paused_lock_hashes, &[&user_a, &user_b]);
+    test_context
+        .context
+        .verify_tx(&proven_tx, u64::MAX)
+        .expect("Proven Transfer Tx Failed");
+
+    let paused_receiver_tx =
+        build_udt_tx(&test_context, vec![udt_input], vec![(&paused_user, 100)])
+            .as_advanced_builder()
+            .cell_dep(smt_registry_dep)
+            .build();
+    let paused_receiver_tx = with_smt_lock_proofs(
+        &paused_receiver_tx,
+        &paused_lock_hashes,
+        &[&user_a, &paused_user],
+    );
+    let paused_receiver_err = test_context
+        .context
+        .verify_tx(&paused_receiver_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(paused_receiver_err, ERROR_ABORTED_FROM_PAUSE);
+}
+
+#[test]
+pub fn test_smt_registry_update() {
+    let mut test_context = build_test_context();
+    let admin = test_context.admin_lock_script.clone();
+    let paused_lock_hash = lock_hash(&test_context.paused_user_lock_script);
+    let other_lock_hash = lock_hash(&test_context.normal_user_a_lock_script);
+    let admin_input = test_context.create_input(&admin);
+    let smt_registry_type_script = test_context
+        .build_registry_type_script(calc_type_id(&admin_input, 0), Some(SMT_REGISTRY_KIND));
+
+    // Each update is proven against the previous root, the way `UDTPausable.smt_pause` builds it
+    let smt_updates = SmtUpdates {
+        updates: vec![SmtUpdate {
+            lock_hash: paused_lock_hash,
+            paused: 1,
+            proof: generate_proof(&[], &paused_lock_hash),
+        }],
+    };
+    let smt_data = |paused_lock_hashes: &[[u8; 32]]| {
+        Bytes::from(
+            to_vec(
+                &UDTPausableSmtData {
+                    root: compute_root_from_leaves(paused_lock_hashes),
+                },
+                false,
+            )
+            .unwrap(),
+        )
+        .pack()
+    };
+    let create_tx = TransactionBuilder::default()
+        .input(admin_input)
+        .output(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(admin)
+                .type_(Some(smt_registry_type_script).pack())
+                .build(),
+        )
+        .output_data(smt_data(&[paused_lock_hash]))
+        .witness(
+            WitnessArgs::new_builder()
+                .output_type(Some(Bytes::from(to_vec(&smt_updates, false).unwrap())).pack())
+                .build()
+                .as_bytes()
+                .pack(),
+        )
+        .cell_deps(test_context.cell_deps())
+        .build();
+    test_context
+        .context
+        .verify_tx(&create_tx, u64::MAX)
+        .expect("Create SMT Registry Cell Tx Failed");
+
+    let unproven_root_tx = create_tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![smt_data(&[other_lock_hash])])
+        .build();
+    let unproven_root_err = test_context
+        .context
+        .verify_tx(&unproven_root_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unproven_root_err, ERROR_INVALID_SMT_PROOF);
+}
+
+#[test]
+pub fn test_restriction_levels() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let paused_user = test_context.paused_user_lock_script.clone();
+    let pausable_data = PausableRegistryData {
+        restriction_list: vec![PauseRestriction {
+            lock_hash: lock_hash(&paused_user),
+            level: RESTRICTION_BLOCK_SEND,
+        }],
+        ..Default::default()
+    };
+
+    // A send-only block still lets the lock receive
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let receive_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&paused_user, 100)]);
+    let receive_tx = with_pause_list(&mut test_context, &receive_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&receive_tx, u64::MAX)
+        .expect("Receive Tx Failed");
+
+    let paused_input = test_context.create_udt_input(&paused_user, 100);
+    let send_tx = build_udt_tx(&test_context, vec![paused_input], vec![(&user_a, 100)]);
+    let send_tx = with_pause_list(&mut test_context, &send_tx, &pausable_data);
+    let send_err = test_context
+        .context
+        .verify_tx(&send_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(send_err, ERROR_ABORTED_FROM_PAUSE);
+}
+
+#[test]
+pub fn test_global_pause() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let admin = test_context.admin_lock_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let pausable_data = PausableRegistryData {
+        global_pause: Some(GlobalPause { end_epoch: 0 }),
+        ..Default::default()
+    };
+
+    // Owner mode is not halted
+    let admin_input = test_context.create_input(&admin);
+    let mint_tx = build_udt_tx(&test_context, vec![admin_input], vec![(&user_b, 100)]);
+    let mint_tx = with_pause_list(&mut test_context, &mint_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&mint_tx, u64::MAX)
+        .expect("Owner Mint Tx Failed");
+
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_b, 100)]);
+    let transfer_tx = with_pause_list(&mut test_context, &transfer_tx, &pausable_data);
+    let transfer_err = test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(transfer_err, ERROR_ABORTED_FROM_GLOBAL_PAUSE);
+}
+
+#[test]
+pub fn test_pause_expiry() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let paused_user = test_context.paused_user_lock_script.clone();
+    let pausable_data = PausableRegistryData {
+        pause_list: vec![lock_hash(&paused_user)],
+        expiry_list: vec![PauseExpiry {
+            lock_hash: lock_hash(&paused_user),
+            expiry_epoch: 10,
+        }],
+        ..Default::default()
+    };
+    let paused_input = test_context.create_udt_input(&paused_user, 100);
+
+    // An absolute epoch `since` proves that the expiry has been reached
+    let expired_input = paused_input
+        .clone()
+        .as_builder()
+        .since(absolute_epoch_since(10).pack())
+        .build();
+    let expired_tx = build_udt_tx(&test_context, vec![expired_input], vec![(&user_a, 100)]);
+    let expired_tx = with_pause_list(&mut test_context, &expired_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&expired_tx, u64::MAX)
+        .expect("Expired Pause Tx Failed");
+
+    let unproven_tx = build_udt_tx(&test_context, vec![paused_input], vec![(&user_a, 100)]);
+    let unproven_tx = with_pause_list(&mut test_context, &unproven_tx, &pausable_data);
+    let unproven_err = test_context
+        .context
+        .verify_tx(&unproven_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unproven_err, ERROR_ABORTED_FROM_PAUSE);
+}
+
+#[test]
+pub fn test_burn_policy() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-burn-owner-only");
+    let admin = test_context.admin_lock_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+
+    let admin_input = test_context.create_input(&admin);
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let owner_burn_tx = build_udt_tx(
+        &test_context,
+        vec![admin_input, udt_input.clone()],
+        vec![(&user_a, 60)],
+    );
+    test_context
+        .context
+        .verify_tx(&owner_burn_tx, u64::MAX)
+        .expect("Owner Burn Tx Failed");
+
+    let holder_burn_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_a, 60)]);
+    let holder_burn_err = test_context
+        .context
+        .verify_tx(&holder_burn_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(holder_burn_err, ERROR_NO_BURN_PERMISSION);
+}
+
+#[test]
+pub fn test_burn_disabled() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-burn-disabled");
+    let admin = test_context.admin_lock_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+
+    let admin_input = test_context.create_input(&admin);
+    let owner_mint_tx = build_udt_tx(
+        &test_context,
+        vec![admin_input.clone()],
+        vec![(&user_a, 100)],
+    );
+    test_context
+        .context
+        .verify_tx(&owner_mint_tx, u64::MAX)
+        .expect("Owner Mint Tx Failed");
+
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let owner_burn_tx = build_udt_tx(
+        &test_context,
+        vec![admin_input, udt_input],
+        vec![(&user_a, 60)],
+    );
+    let owner_burn_err = test_context
+        .context
+        .verify_tx(&owner_burn_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(owner_burn_err, ERROR_NO_BURN_PERMISSION);
+}
+
+#[test]
+pub fn test_supply_tracking() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-supply");
+    let admin = test_context.admin_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let supply_type_script = test_context.build_fixed_script("supply");
+    let supply_data = UDTSupplyData {
+        udt_type_hash: lock_hash(&test_context.pausable_udt_type_script),
+        total_supply: 100,
+        max_supply: 1000,
+    };
+
+    let admin_input = test_context.create_input(&admin);
+    let untracked_mint_tx = build_udt_tx(&test_context, vec![admin_input], vec![(&user_b, 100)]);
+    let tracked_mint_tx = untracked_mint_tx
+        .as_advanced_builder()
+        .output(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(admin)
+                .type_(Some(supply_type_script).pack())
+                .build(),
+        )
+        .output_data(Bytes::from(to_vec(&supply_data, false).unwrap()).pack())
+        .build();
+    test_context
+        .context
+        .verify_tx(&tracked_mint_tx, u64::MAX)
+        .expect("Tracked Mint Tx Failed");
+
+    let untracked_mint_err = test_context
+        .context
+        .verify_tx(&untracked_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(untracked_mint_err, ERROR_SUPPLY_NOT_TRACKED);
+}
+
+#[test]
+pub fn test_supply_cell_update() {
+    let mut test_context = build_test_context();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let supply_type_script = test_context.build_registry_type_script([1u8; 32], Some(SUPPLY_KIND));
+    let supply_cell = |lock: &Script| {
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(lock.clone())
+            .type_(Some(supply_type_script.clone()).pack())
+            .build()
+    };
+    let udt_type_hash = lock_hash(&test_context.pausable_udt_type_script);
+    let supply_data = |total_supply: u128| {
+        Bytes::from(
+            to_vec(
+                &UDTSupplyData {
+                    udt_type_hash,
+                    total_supply,
+                    max_supply: 1000,
+                },
+                false,
+            )
+            .unwrap(),
+        )
+    };
+    let supply_out_point = test_context
+        .context
+        .create_cell(supply_cell(&user_a), supply_data(100));
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+
+    // A holder burning 10 tokens brings the total supply down by as much
+    let burn_tx = build_udt_tx(
+        &test_context,
+        vec![
+            CellInput::new_builder()
+                .previous_output(supply_out_point)
+                .build(),
+            udt_input,
+        ],
+        vec![(&user_a, 90)],
+    )
+    .as_advanced_builder()
+    .output(supply_cell(&user_a))
+    .output_data(supply_data(90).pack())
+    .build();
+    test_context
+        .context
+        .verify_tx(&burn_tx, u64::MAX)
+        .expect("Burn Tx Failed");
+
+    // Out of owner mode, the supply cell must keep its lock
+    let moved_supply_tx = burn_tx
+        .as_advanced_builder()
+        .set_outputs(vec![test_context.udt_output(&user_a), supply_cell(&user_b)])
+        .build();
+    let moved_supply_err = test_context
+        .context
+        .verify_tx(&moved_supply_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(moved_supply_err, ERROR_INVALID_SUPPLY_DATA);
+}
+
+#[test]
+pub fn test_owner_mode_by_input_type() {
+    let mut test_context = build_test_context();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let owner_type_script = test_context.build_fixed_script("owner type");
+    let mut args = owner_type_script.calc_script_hash().as_bytes().to_vec();
+    args.extend_from_slice(&OWNER_MODE_INPUT_TYPE_MASK.to_le_bytes());
+    let udt_type_script = test_context
+        .context
+        .build_script(&test_context.pausable_udt_out_point, Bytes::from(args))
+        .expect("script");
+    let udt_output = CellOutput::new_builder()
+        .capacity(100u64.pack())
+        .lock(user_b)
+        .type_(Some(udt_type_script).pack())
+        .build();
+
+    let owner_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(user_a.clone())
+            .type_(Some(owner_type_script.clone()).pack())
+            .build(),
+        Bytes::default(),
+    );
+    let owner_mint_tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(owner_out_point)
+                .build(),
+        )
+        .output(udt_output.clone())
+        .output_data(udt_data(100).pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+    test_context
+        .context
+        .verify_tx(&owner_mint_tx, u64::MAX)
+        .expect("Owner Type Mint Tx Failed");
+
+    let user_input = test_context.create_input(&user_a);
+    let unauthorized_mint_tx = owner_mint_tx
+        .as_advanced_builder()
+        .set_inputs(vec![user_input])
+        .build();
+    let unauthorized_mint_err = test_context
+        .context
+        .verify_tx(&unauthorized_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unauthorized_mint_err, ERROR_INSUFFICIENT_BALANCE);
+
+    let mut long_args = owner_type_script.calc_script_hash().as_bytes().to_vec();
+    long_args.extend_from_slice(&[0u8; 8]);
+    let long_args_type_script = test_context
+        .context
+        .build_script(&test_context.pausable_udt_out_point, Bytes::from(long_args))
+        .expect("script");
+    let long_args_mint_tx = owner_mint_tx
+        .as_advanced_builder()
+        .set_outputs(vec![udt_output
+            .as_builder()
+            .type_(Some(long_args_type_script).pack())
+            .build()])
+        .build();
+    let long_args_mint_err = test_context
+        .context
+        .verify_tx(&long_args_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(long_args_mint_err, ERROR_INVALID_TOKEN_ARGS);
+}
+
+#[test]
+pub fn test_minter_role() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-roles");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let roles_type_script = test_context.build_fixed_script("roles");
+    let roles_data = UDTRolesData {
+        roles: vec![RoleMembers {
+            role: 1,
+            lock_hashes: vec![lock_hash(&user_a)],
+        }],
+    };
+    let roles_dep = test_context.create_cell_dep(
+        &roles_type_script,
+        Bytes::from(to_vec(&roles_data, false).unwrap()),
+    );
+
+    let minter_input = test_context.create_input(&user_a);
+    let minter_mint_tx = build_udt_tx(&test_context, vec![minter_input], vec![(&user_b, 100)])
+        .as_advanced_builder()
+        .cell_dep(roles_dep.clone())
+        .build();
+    test_context
+        .context
+        .verify_tx(&minter_mint_tx, u64::MAX)
+        .expect("Minter Mint Tx Failed");
+
+    let user_input = test_context.create_input(&user_b);
+    let unauthorized_mint_tx = build_udt_tx(&test_context, vec![user_input], vec![(&user_b, 100)])
+        .as_advanced_builder()
+        .cell_dep(roles_dep)
+        .build();
+    let unauthorized_mint_err = test_context
+        .context
+        .verify_tx(&unauthorized_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unauthorized_mint_err, ERROR_INSUFFICIENT_BALANCE);
+}
+
+#[test]
+pub fn test_extension_data_format() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-extension-table");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_b, 100)]);
+    let with_extension_data = |extension_data: &[u8]| {
+        let mut data = udt_data(100).to_vec();
+        data.extend_from_slice(extension_data);
+        transfer_tx
+            .as_advanced_builder()
+            .set_outputs_data(vec![Bytes::from(data).pack()])
+            .build()
+    };
+
+    let table_tx = with_extension_data(&to_vec(&PausableRegistryData::default(), false).unwrap());
+    test_context
+        .context
+        .verify_tx(&table_tx, u64::MAX)
+        .expect("Molecule Table Extension Tx Failed");
+
+    let invalid_extension_err = test_context
+        .context
+        .verify_tx(&with_extension_data(&[1, 2, 3]), u64::MAX)
+        .unwrap_err();
+    assert_script_error(invalid_extension_err, ERROR_INVALID_EXTENSION_DATA);
+}
+
+#[test]
+pub fn test_frozen_cells() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let frozen_input = test_context.create_udt_input(&user_a, 100);
+    let pausable_data = PausableRegistryData {
+        frozen_cells: vec![FrozenCell {
+            tx_hash: frozen_input.previous_output().tx_hash().unpack(),
+            index: frozen_input.previous_output().index().unpack(),
+        }],
+        ..Default::default()
+    };
+
+    // Other cells of the same lock are not affected
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_b, 100)]);
+    let transfer_tx = with_pause_list(&mut test_context, &transfer_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .expect("Transfer Tx Failed");
+
+    let frozen_tx = build_udt_tx(&test_context, vec![frozen_input], vec![(&user_b, 100)]);
+    let frozen_tx = with_pause_list(&mut test_context, &frozen_tx, &pausable_data);
+    let frozen_err = test_context
+        .context
+        .verify_tx(&frozen_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(frozen_err, ERROR_ABORTED_FROM_FROZEN_CELL);
+}
+
+#[test]
+pub fn test_allowlist() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-allowlist");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let paused_user = test_context.paused_user_lock_script.clone();
+    let allowlist_type_script = test_context.build_fixed_script("allowlist");
+    let allowlist_data = UDTAllowlistData {
+        allow_list: vec![lock_hash(&user_b)],
+        next_type_script: None,
+    };
+    let allowlist_dep = test_context.create_cell_dep(
+        &allowlist_type_script,
+        Bytes::from(to_vec(&allowlist_data, false).unwrap()),
+    );
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+
+    let allowed_tx = build_udt_tx(&test_context, vec![udt_input.clone()], vec![(&user_b, 100)])
+        .as_advanced_builder()
+        .cell_dep(allowlist_dep.clone())
+        .build();
+    test_context
+        .context
+        .verify_tx(&allowed_tx, u64::MAX)
+        .expect("Allowed Transfer Tx Failed");
+
+    let not_allowed_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&paused_user, 100)])
+        .as_advanced_builder()
+        .cell_dep(allowlist_dep)
+        .build();
+    let not_allowed_err = test_context
+        .context
+        .verify_tx(&not_allowed_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(not_allowed_err, ERROR_ABORTED_FROM_ALLOWLIST);
+}
+
+#[test]
+pub fn test_clawback() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let admin = test_context.admin_lock_script.clone();
+    let paused_user = test_context.paused_user_lock_script.clone();
+    let recovery_lock = test_context.build_fixed_script("recovery");
+    let pausable_data = PausableRegistryData {
+        pause_list: vec![lock_hash(&paused_user)],
+        ..Default::default()
+    };
+    let admin_input = test_context.create_input(&admin);
+    let paused_input = test_context.create_udt_input(&paused_user, 100);
+
+    let clawback_tx = build_udt_tx(
+        &test_context,
+        vec![admin_input.clone(), paused_input.clone()],
+        vec![(&recovery_lock, 100)],
+    );
+    let clawback_tx = with_pause_list(&mut test_context, &clawback_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&clawback_tx, u64::MAX)
+        .expect("Clawback Tx Failed");
+
+    // Paused funds may only go to the recovery lock, even in owner mode
+    let seizure_tx = build_udt_tx(
+        &test_context,
+        vec![admin_input, paused_input],
+        vec![(&admin, 100)],
+    );
+    let seizure_tx = with_pause_list(&mut test_context, &seizure_tx, &pausable_data);
+    let seizure_err = test_context
+        .context
+        .verify_tx(&seizure_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(seizure_err, ERROR_ABORTED_FROM_PAUSE);
+}
+
+#[test]
+pub fn test_transfer_limits() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-limits");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let watched_lock = test_context.build_fixed_script("watched");
+
+    let udt_input = test_context.create_udt_input(&user_a, 1000);
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_b, 1000)]);
+    test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .expect("Transfer Within Limit Tx Failed");
+
+    // Watched locks have a lower limit
+    let watched_input = test_context.create_udt_input(&watched_lock, 1000);
+    let watched_tx = build_udt_tx(
+        &test_context,
+        vec![watched_input],
+        vec![(&user_b, 101), (&watched_lock, 899)],
+    );
+    let watched_err = test_context
+        .context
+        .verify_tx(&watched_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(watched_err, ERROR_EXCEEDED_TRANSFER_LIMIT);
+}
+
+#[test]
+pub fn test_transfer_limit_with_recipient_input() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-limits");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let recipient_input = test_context.create_udt_input(&user_b, 1);
+
+    let sender_input = test_context.create_udt_input(&user_a, 1000);
+    let transfer_tx = build_udt_tx(
+        &test_context,
+        vec![sender_input, recipient_input.clone()],
+        vec![(&user_b, 1001)],
+    );
+    test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .expect("Transfer Within Limit Tx Failed");
+
+    // The dust input of the recipient does not turn what it receives into change
+    let sender_input = test_context.create_udt_input(&user_a, 1001);
+    let drain_tx = build_udt_tx(
+        &test_context,
+        vec![sender_input, recipient_input],
+        vec![(&user_b, 1002)],
+    );
+    let drain_err = test_context
+        .context
+        .verify_tx(&drain_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(drain_err, ERROR_EXCEEDED_TRANSFER_LIMIT);
+}
+
+#[test]
+pub fn test_transfer_fee() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-fee");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let treasury_lock = test_context.build_fixed_script("treasury");
+    let udt_input = test_context.create_udt_input(&user_a, 10100);
+
+    let fee_paid_tx = build_udt_tx(
+        &test_context,
+        vec![udt_input.clone()],
+        vec![(&user_b, 10000), (&treasury_lock, 100)],
+    );
+    test_context
+        .context
+        .verify_tx(&fee_paid_tx, u64::MAX)
+        .expect("Fee Paid Transfer Tx Failed");
+
+    let fee_unpaid_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_b, 10100)]);
+    let fee_unpaid_err = test_context
+        .context
+        .verify_tx(&fee_unpaid_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(fee_unpaid_err, ERROR_INSUFFICIENT_TRANSFER_FEE);
+}
+
+#[test]
+pub fn test_transfer_fee_with_recipient_input() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-fee");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let treasury_lock = test_context.build_fixed_script("treasury");
+    let sender_input = test_context.create_udt_input(&user_a, 10100);
+    let recipient_input = test_context.create_udt_input(&user_b, 1);
+
+    let fee_paid_tx = build_udt_tx(
+        &test_context,
+        vec![sender_input.clone(), recipient_input.clone()],
+        vec![(&user_b, 10001), (&treasury_lock, 100)],
+    );
+    test_context
+        .context
+        .verify_tx(&fee_paid_tx, u64::MAX)
+        .expect("Fee Paid Transfer Tx Failed");
+
+    // The input of the recipient does not turn what it receives into change
+    let fee_unpaid_tx = build_udt_tx(
+        &test_context,
+        vec![sender_input, recipient_input],
+        vec![(&user_b, 10101)],
+    );
+    let fee_unpaid_err = test_context
+        .context
+        .verify_tx(&fee_unpaid_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(fee_unpaid_err, ERROR_INSUFFICIENT_TRANSFER_FEE);
+}
+
+#[test]
+pub fn test_mint_quota() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-quota");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let quota_type_script = test_context.build_fixed_script("quota");
+    let quota_cell = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(user_a.clone())
+        .type_(Some(quota_type_script).pack())
+        .build();
+    let udt_type_hash = lock_hash(&test_context.pausable_udt_type_script);
+    let quota_data = |remaining_quota: u128| {
+        Bytes::from(
+            to_vec(
+                &UDTQuotaData {
+                    udt_type_hash,
+                    quotas: vec![MinterQuota {
+                        lock_hash: lock_hash(&user_a),
+                        remaining_quota,
+                    }],
+                },
+                false,
+            )
+            .unwrap(),
+        )
+    };
+    let quota_out_point = test_context
+        .context
+        .create_cell(quota_cell.clone(), quota_data(1000));
+    let minter_input = test_context.create_input(&user_a);
+
+    let quota_mint_tx = build_udt_tx(
+        &test_context,
+        vec![
+            minter_input.clone(),
+            CellInput::new_builder()
+                .previous_output(quota_out_point)
+                .build(),
+        ],
+        vec![(&user_b, 100)],
+    )
+    .as_advanced_builder()
+    .output(quota_cell)
+    .output_data(quota_data(900).pack())
+    .build();
+    test_context
+        .context
+        .verify_tx(&quota_mint_tx, u64::MAX)
+        .expect("Quota Mint Tx Failed");
+
+    let untracked_mint_tx = build_udt_tx(&test_context, vec![minter_input], vec![(&user_b, 100)]);
+    let untracked_mint_err = test_context
+        .context
+        .verify_tx(&untracked_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(untracked_mint_err, ERROR_QUOTA_NOT_TRACKED);
+}
+
+#[test]
+pub fn test_quota_cell_update() {
+    let mut test_context = build_test_context();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    // The quota cell only counts the tokens of its `udt_type_hash`
+    let token_type_script = test_context.build_fixed_script("token");
+    let quota_type_script = test_context.build_registry_type_script([2u8; 32], Some(QUOTA_KIND));
+    let quota_cell = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(user_b.clone())
+        .type_(Some(quota_type_script).pack())
+        .build();
+    let quota_data = |remaining_quota: u128| {
+        Bytes::from(
+            to_vec(
+                &UDTQuotaData {
+                    udt_type_hash: lock_hash(&token_type_script),
+                    quotas: vec![MinterQuota {
+                        lock_hash: lock_hash(&user_a),
+                        remaining_quota,
+                    }],
+                },
+                false,
+            )
+            .unwrap(),
+        )
+    };
+    let quota_out_point = test_context
+        .context
+        .create_cell(quota_cell.clone(), quota_data(1000));
+    let minter_input = test_context.create_input(&user_a);
+
+    let spend_quota_tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(quota_out_point)
+                .build(),
+        )
+        .input(minter_input)
+        .output(quota_cell)
+        .output_data(quota_data(900).pack())
+        .output(
+            CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(user_b)
+                .type_(Some(token_type_script.clone()).pack())
+                .build(),
+        )
+        .output_data(udt_data(100).pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+    test_context
+        .context
+        .verify_tx(&spend_quota_tx, u64::MAX)
+        .expect("Spend Quota Tx Failed");
+
+    let underspent_quota_tx = spend_quota_tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![quota_data(950).pack(), udt_data(100).pack()])
+        .build();
+    let underspent_quota_err = test_context
+        .context
+        .verify_tx(&underspent_quota_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(underspent_quota_err, ERROR_EXCEEDED_MINT_QUOTA);
+}
+
+#[test]
+pub fn test_owner_mint_quota() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-quota");
+    let admin = test_context.admin_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let quota_type_script = test_context.build_fixed_script("quota");
+    let quota_cell = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(admin.clone())
+        .type_(Some(quota_type_script).pack())
+        .build();
+    let quota_data = Bytes::from(
+        to_vec(
+            &UDTQuotaData {
+                udt_type_hash: lock_hash(&test_context.pausable_udt_type_script),
+                quotas: vec![],
+            },
+            false,
+        )
+        .unwrap(),
+    );
+    let quota_out_point = test_context
+        .context
+        .create_cell(quota_cell.clone(), quota_data.clone());
+    let admin_input = test_context.create_input(&admin);
+
+    let quota_mint_tx = build_udt_tx(
+        &test_context,
+        vec![
+            admin_input.clone(),
+            CellInput::new_builder()
+                .previous_output(quota_out_point)
+                .build(),
+        ],
+        vec![(&user_b, 100)],
+    )
+    .as_advanced_builder()
+    .output(quota_cell)
+    .output_data(quota_data.pack())
+    .build();
+    test_context
+        .context
+        .verify_tx(&quota_mint_tx, u64::MAX)
+        .expect("Owner Quota Mint Tx Failed");
+
+    // Owner mode mints through the quota cell like any minter
+    let untracked_mint_tx = build_udt_tx(&test_context, vec![admin_input], vec![(&user_b, 100)]);
+    let untracked_mint_err = test_context
+        .context
+        .verify_tx(&untracked_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(untracked_mint_err, ERROR_QUOTA_NOT_TRACKED);
+}
+
+#[test]
+pub fn test_owner_quota_cell_update() {
+    let mut test_context = build_test_context();
+    let admin = test_context.admin_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let token_type_script = test_context.build_fixed_script("token");
+    let quota_type_script = test_context.build_registry_type_script([9u8; 32], Some(QUOTA_KIND));
+    let quota_cell = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(admin.clone())
+        .type_(Some(quota_type_script).pack())
+        .build();
+    let quota_data = |remaining_quota: u128| {
+        Bytes::from(
+            to_vec(
+                &UDTQuotaData {
+                    udt_type_hash: lock_hash(&token_type_script),
+                    quotas: vec![MinterQuota {
+                        lock_hash: lock_hash(&admin),
+                        remaining_quota,
+                    }],
+                },
+                false,
+            )
+            .unwrap(),
+        )
+    };
+    let quota_out_point = test_context
+        .context
+        .create_cell(quota_cell.clone(), quota_data(1000));
+    let admin_input = test_context.create_input(&admin);
+
+    // The owner spends its own entry when it mints
+    let spend_quota_tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(quota_out_point)
+                .build(),
+        )
+        .input(admin_input)
+        .output(quota_cell)
+        .output_data(quota_data(900).pack())
+        .output(
+            CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(user_b)
+                .type_(Some(token_type_script.clone()).pack())
+                .build(),
+        )
+        .output_data(udt_data(100).pack())
+        .cell_deps(test_context.cell_deps())
+        .build();
+    test_context
+        .context
+        .verify_tx(&spend_quota_tx, u64::MAX)
+        .expect("Owner Spend Quota Tx Failed");
+
+    let unspent_quota_tx = spend_quota_tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![quota_data(1000).pack(), udt_data(100).pack()])
+        .build();
+    let unspent_quota_err = test_context
+        .context
+        .verify_tx(&unspent_quota_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unspent_quota_err, ERROR_EXCEEDED_MINT_QUOTA);
+}
+
+#[test]
+pub fn test_lock_code_hash_rules() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    // The locks of the test context use hash type Type, so they are outside of this family
+    let family_lock = test_context.build_fixed_script("family");
+    let pausable_data = PausableRegistryData {
+        lock_code_hash_rules: vec![LockCodeHashRule {
+            code_hash: family_lock.code_hash().unpack(),
+            hash_type: family_lock.hash_type().into(),
+        }],
+        ..Default::default()
+    };
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input.clone()], vec![(&user_b, 100)]);
+    let transfer_tx = with_pause_list(&mut test_context, &transfer_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .expect("Transfer Tx Failed");
+
+    let family_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&family_lock, 100)]);
+    let family_tx = with_pause_list(&mut test_context, &family_tx, &pausable_data);
+    let family_err = test_context
+        .context
+        .verify_tx(&family_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(family_err, ERROR_ABORTED_FROM_PAUSE);
+}
+
+#[test]
+pub fn test_blocked_type_hashes() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let blocked_type_script = test_context.build_fixed_script("exploited dex");
+    let pausable_data = PausableRegistryData {
+        blocked_type_hashes: vec![lock_hash(&blocked_type_script)],
+        ..Default::default()
+    };
+    let udt_input = test_context.create_udt_input(&user_a, 100);
+
+    let transfer_tx = build_udt_tx(&test_context, vec![udt_input], vec![(&user_b, 100)]);
+    let transfer_tx = with_pause_list(&mut test_context, &transfer_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .expect("Transfer Tx Failed");
+
+    let blocked_tx = transfer_tx
+        .as_advanced_builder()
+        .output(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(user_a)
+                .type_(Some(blocked_type_script).pack())
+                .build(),
+        )
+        .output_data(Bytes::default().pack())
+        .build();
+    let blocked_err = test_context
+        .context
+        .verify_tx(&blocked_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(blocked_err, ERROR_ABORTED_FROM_BLOCKED_TYPE);
+}
+
+#[test]
+pub fn test_guardian_update() {
+    let mut test_context = build_test_context();
+    let guardian = test_context.normal_user_a_lock_script.clone();
+    let paused_lock_hash = lock_hash(&test_context.paused_user_lock_script);
+    let registry_type_script = test_context.build_registry_type_script([3u8; 32], None);
+    let unpaused_data = PausableRegistryData {
+        guardian_lock_hash: Some(lock_hash(&guardian)),
+        ..Default::default()
+    };
+    let paused_data = PausableRegistryData {
+        pause_list: vec![paused_lock_hash],
+        ..unpaused_data.clone()
+    };
+
+    let guardian_input = test_context.create_input(&guardian);
+    let pause_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &unpaused_data,
+        &paused_data,
+        vec![guardian_input.clone()],
+    );
+    test_context
+        .context
+        .verify_tx(&pause_tx, u64::MAX)
+        .expect("Guardian Pause Tx Failed");
+
+    // The guardian can never lift a restriction
+    let unpause_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &paused_data,
+        &unpaused_data,
+        vec![guardian_input],
+    );
+    let unpause_err = test_context
+        .context
+        .verify_tx(&unpause_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unpause_err, ERROR_NO_PAUSE_PERMISSION);
+}
+
+#[test]
+pub fn test_unpause_delay() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-unpause-delay");
+    let admin = test_context.admin_lock_script.clone();
+    let paused_lock_hash = lock_hash(&test_context.paused_user_lock_script);
+    let registry_type_script = test_context.build_registry_type_script([4u8; 32], None);
+    let paused_data = PausableRegistryData {
+        pause_list: vec![paused_lock_hash],
+        ..Default::default()
+    };
+    let scheduled_data = PausableRegistryData {
+        pending_unpauses: vec![PendingUnpause {
+            lock_hash: paused_lock_hash,
+            scheduled_epoch: 5,
+        }],
+        ..paused_data.clone()
+    };
+    let admin_input = test_context.create_input(&admin);
+    // The `since` of an input proves the current epoch
+    let admin_input_at = |epoch: u64| {
+        admin_input
+            .clone()
+            .as_builder()
+            .since(absolute_epoch_since(epoch).pack())
+            .build()
+    };
+
+    // The registry cell was last updated at epoch 5, and the delay of 2 epochs has passed since then
+    let finalize_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &scheduled_data,
+        &PausableRegistryData::default(),
+        vec![admin_input_at(7)],
+    );
+    let finalize_tx = with_first_input_header(&mut test_context, &finalize_tx, 5);
+    test_context
+        .context
+        .verify_tx(&finalize_tx, u64::MAX)
+        .expect("Finalize Unpause Tx Failed");
+
+    let early_finalize_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &scheduled_data,
+        &PausableRegistryData::default(),
+        vec![admin_input_at(6)],
+    );
+    let early_finalize_tx = with_first_input_header(&mut test_context, &early_finalize_tx, 5);
+    let early_finalize_err = test_context
+        .context
+        .verify_tx(&early_finalize_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(early_finalize_err, ERROR_UNPAUSE_NOT_DUE);
+
+    let unscheduled_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &paused_data,
+        &PausableRegistryData::default(),
+        vec![admin_input_at(7)],
+    );
+    let unscheduled_tx = with_first_input_header(&mut test_context, &unscheduled_tx, 5);
+    let unscheduled_err = test_context
+        .context
+        .verify_tx(&unscheduled_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unscheduled_err, ERROR_UNPAUSE_NOT_SCHEDULED);
+}
+
+#[test]
+pub fn test_approval_threshold() {
+    let mut test_context = build_test_context();
+    let approver_a = test_context.normal_user_a_lock_script.clone();
+    let approver_b = test_context.normal_user_b_lock_script.clone();
+    let paused_lock_hash = lock_hash(&test_context.paused_user_lock_script);
+    let registry_type_script = test_context.build_registry_type_script([5u8; 32], None);
+    let mut approvers = vec![lock_hash(&approver_a), lock_hash(&approver_b)];
+    approvers.sort();
+    let unpaused_data = PausableRegistryData {
+        approvers,
+        approval_threshold: 2,
+        ..Default::default()
+    };
+    let paused_data = PausableRegistryData {
+        pause_list: vec![paused_lock_hash],
+        ..unpaused_data.clone()
+    };
+    let approver_a_input = test_context.create_input(&approver_a);
+    let approver_b_input = test_context.create_input(&approver_b);
+
+    let approved_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &unpaused_data,
+        &paused_data,
+        vec![approver_a_input.clone(), approver_b_input],
+    );
+    test_context
+        .context
+        .verify_tx(&approved_tx, u64::MAX)
+        .expect("Approved Pause Tx Failed");
+
+    let single_approval_tx = build_registry_update_tx(
+        &mut test_context,
+        &registry_type_script,
+        &unpaused_data,
+        &paused_data,
+        vec![approver_a_input],
+    );
+    let single_approval_err = test_context
+        .context
+        .verify_tx(&single_approval_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(single_approval_err, ERROR_NOT_ENOUGH_APPROVALS);
+}
+
+#[test]
+pub fn test_pause_proposal_approval() {
+    let mut test_context = build_test_context();
+    let approver_a = test_context.normal_user_a_lock_script.clone();
+    let approver_b = test_context.normal_user_b_lock_script.clone();
+    let paused_lock_hash = lock_hash(&test_context.paused_user_lock_script);
+    let approver_a_input = test_context.create_input(&approver_a);
+    let proposal_type_script = test_context
+        .build_registry_type_script(calc_type_id(&approver_a_input, 0), Some(PROPOSAL_KIND));
+    let proposal_data = |approvals: Vec<[u8; 32]>| {
+        Bytes::from(
+            to_vec(
+                &UDTPauseProposalData {
+                    registry_type_hash: [6u8; 32],
+                    pause_list_additions: vec![paused_lock_hash],
+                    pause_list_removals: vec![],
+                    approvals,
+                },
+                false,
+            )
+            .unwrap(),
+        )
+        .pack()
+    };
+
+    // Anyone may open a proposal, approving it with a lock among the inputs
+    let open_proposal_tx = TransactionBuilder::default()
+        .input(approver_a_input)
+        .output(registry_output(&mut test_context, &proposal_type_script))
+        .output_data(proposal_data(vec![lock_hash(&approver_a)]))
+        .cell_deps(test_context.cell_deps())
+        .build();
+    test_context
+        .context
+        .verify_tx(&open_proposal_tx, u64::MAX)
+        .expect("Open Proposal Tx Failed");
+
+    let mut approvals = vec![lock_hash(&approver_a), lock_hash(&approver_b)];
+    approvals.sort();
+    let unsigned_approval_tx = open_proposal_tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![proposal_data(approvals)])
+        .build();
+    let unsigned_approval_err = test_context
+        .context
+        .verify_tx(&unsigned_approval_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unsigned_approval_err, ERROR_INVALID_APPROVAL);
+}
+
+#[test]
+pub fn test_pause_proposal_execution() {
+    let mut test_context = build_test_context();
+    let approver_a = test_context.normal_user_a_lock_script.clone();
+    let approver_b = test_context.normal_user_b_lock_script.clone();
+    let paused_lock_hash = lock_hash(&test_context.paused_user_lock_script);
+    let registry_type_script = test_context.build_registry_type_script([7u8; 32], None);
+    let proposal_type_script =
+        test_context.build_registry_type_script([8u8; 32], Some(PROPOSAL_KIND));
+    let mut approvers = vec![lock_hash(&approver_a), lock_hash(&approver_b)];
+    approvers.sort();
+    let unpaused_data = PausableRegistryData {
+        approvers: approvers.clone(),
+        approval_threshold: 2,
+        ..Default::default()
+    };
+    let paused_data = PausableRegistryData {
+        pause_list: vec![paused_lock_hash],
+        ..unpaused_data.clone()
+    };
+    let mut build_execution_tx = |approvals: Vec<[u8; 32]>| {
+        let proposal_data = UDTPauseProposalData {
+            registry_type_hash: lock_hash(&registry_type_script),
+            pause_list_additions: vec![paused_lock_hash],
+            pause_list_removals: vec![],
+            approvals,
+        };
+        let proposal_input = create_registry_input(
+            &mut test_context,
+            &proposal_type_script,
+            Bytes::from(to_vec(&proposal_data, false).unwrap()),
+        );
+        build_registry_update_tx(
+            &mut test_context,
+            &registry_type_script,
+            &unpaused_data,
+            &paused_data,
+            vec![proposal_input],
+        )
+    };
+
+    // The approvals collected by the proposal stand in for approver inputs
+    let approved_tx = build_execution_tx(approvers);
+    let unapproved_tx = build_execution_tx(vec![lock_hash(&approver_a)]);
+    test_context
+        .context
+        .verify_tx(&approved_tx, u64::MAX)
+        .expect("Execute Proposal Tx Failed");
+
+    let unapproved_err = test_context
+        .context
+        .verify_tx(&unapproved_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(unapproved_err, ERROR_PROPOSAL_NOT_APPROVED);
+}
+
+#[test]
+pub fn test_redemption() {
+    let mut test_context = build_test_context_with_binary("pausable-udt-test-registry");
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let paused_user = test_context.paused_user_lock_script.clone();
+    let redemption_lock = test_context.build_fixed_script("redemption");
+    let pausable_data = PausableRegistryData {
+        pause_list: vec![lock_hash(&paused_user)],
+        redemption_lock_hashes: vec![lock_hash(&redemption_lock)],
+        ..Default::default()
+    };
+    let paused_input = test_context.create_udt_input(&paused_user, 100);
+
+    let redemption_tx = build_udt_tx(
+        &test_context,
+        vec![paused_input.clone()],
+        vec![(&redemption_lock, 100)],
+    );
+    let redemption_tx = with_pause_list(&mut test_context, &redemption_tx, &pausable_data);
+    test_context
+        .context
+        .verify_tx(&redemption_tx, u64::MAX)
+        .expect("Redemption Tx Failed");
+
+    let paused_transfer_tx = build_udt_tx(&test_context, vec![paused_input], vec![(&user_b, 100)]);
+    let paused_transfer_tx =
+        with_pause_list(&mut test_context, &paused_transfer_tx, &pausable_data);
+    let paused_transfer_err = test_context
+        .context
+        .verify_tx(&paused_transfer_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(paused_transfer_err, ERROR_ABORTED_FROM_PAUSE);
+}
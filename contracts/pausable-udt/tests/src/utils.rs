@@ -1,11 +1,12 @@
 use ckb_hash::blake2b_256;
 use ckb_std::{
-    ckb_types::{bytes::Bytes, packed::*, prelude::*},
+    ckb_types::{bytes::Bytes, core::ScriptHashType, packed::*, prelude::*},
     high_level::encode_hex,
 };
 use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
 use reqwest::Client;
 
+use crate::cell_data::PausableRegistryData;
 use crate::Loader;
 
 pub fn method_path(name: impl AsRef<[u8]>) -> u64 {
@@ -48,6 +49,7 @@ pub async fn get_ssri_response(payload: serde_json::Value) -> serde_json::Value
 pub struct PausableUDTTestContext {
     pub context: Context,
     pub pausable_udt_out_point: OutPoint,
+    pub always_success_out_point: OutPoint,
     pub always_success_dep: CellDep,
     pub pausable_udt_dep: CellDep,
     pub pausable_udt_type_script: Script,
@@ -58,6 +60,11 @@ pub struct PausableUDTTestContext {
 }
 
 pub fn build_test_context() -> PausableUDTTestContext {
+    build_test_context_with_binary("pausable-udt")
+}
+
+/// Same as `build_test_context`, with one of the `pausable-udt-test-<name>` test builds of `make build-tests` as the token script.
+pub fn build_test_context_with_binary(binary_name: &str) -> PausableUDTTestContext {
     let admin_args = String::from("00018fd14cc327648651dc0ac81ec6dd63a9ab376e61");
     let normal_user_a_args = String::from("00018fd14cc327648651dc0ac81ec6dd63a9ab376e62");
     let normal_user_b_args = String::from("00018fd14cc327648651dc0ac81ec6dd63a9ab376e63");
@@ -65,7 +72,7 @@ pub fn build_test_context() -> PausableUDTTestContext {
 
     let mut context = Context::default();
     let loader = Loader::default();
-    let pausable_udt_bin = loader.load_binary("pausable-udt");
+    let pausable_udt_bin = loader.load_binary(binary_name);
     let pausable_udt_out_point = context.deploy_cell(pausable_udt_bin);
     let pausable_udt_dep = CellDep::new_builder()
         .out_point(pausable_udt_out_point.clone())
@@ -110,6 +117,7 @@ pub fn build_test_context() -> PausableUDTTestContext {
     PausableUDTTestContext {
         context,
         pausable_udt_out_point,
+        always_success_out_point,
         always_success_dep,
         pausable_udt_dep,
         pausable_udt_type_script,
@@ -119,3 +127,227 @@ pub fn build_test_context() -> PausableUDTTestContext {
         paused_user_lock_script,
     }
 }
+
+impl PausableUDTTestContext {
+    /// Builds an always-success script with hash type Data1 and `name` as args. Its hash does not depend on the deployment, which is how the files in `configs` configure cells by name.
+    pub fn build_fixed_script(&mut self, name: &str) -> Script {
+        self.context
+            .build_script_with_hash_type(
+                &self.always_success_out_point,
+                ScriptHashType::Data1,
+                Bytes::from(name.as_bytes().to_vec()),
+            )
+            .expect("script")
+    }
+
+    /// Builds the type script of a registry cell of the token, with the admin lock as owner and `kind` appended for the cells that have one.
+    pub fn build_registry_type_script(&mut self, type_id: [u8; 32], kind: Option<u8>) -> Script {
+        let mut args = self.admin_lock_script.calc_script_hash().as_bytes().to_vec();
+        args.extend_from_slice(&type_id);
+        args.extend(kind);
+        self.context
+            .build_script(&self.pausable_udt_out_point, Bytes::from(args))
+            .expect("script")
+    }
+
+    pub fn create_input(&mut self, lock: &Script) -> CellInput {
+        let out_point = self.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(lock.clone())
+                .build(),
+            Bytes::default(),
+        );
+        CellInput::new_builder().previous_output(out_point).build()
+    }
+
+    pub fn create_udt_input(&mut self, lock: &Script, amount: u128) -> CellInput {
+        let out_point = self.context.create_cell(
+            self.udt_output(lock),
+            Bytes::from(amount.to_le_bytes().to_vec()),
+        );
+        CellInput::new_builder().previous_output(out_point).build()
+    }
+
+    pub fn udt_output(&self, lock: &Script) -> CellOutput {
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(lock.clone())
+            .type_(Some(self.pausable_udt_type_script.clone()).pack())
+            .build()
+    }
+
+    /// Creates a cell typed by `type_script` holding `data`, and returns it as a cell dep.
+    pub fn create_cell_dep(&mut self, type_script: &Script, data: Bytes) -> CellDep {
+        let out_point = self.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(self.admin_lock_script.clone())
+                .type_(Some(type_script.clone()).pack())
+                .build(),
+            data,
+        );
+        CellDep::new_builder().out_point(out_point).build()
+    }
+
+    pub fn cell_deps(&self) -> Vec<CellDep> {
+        vec![
+            self.pausable_udt_dep.clone(),
+            self.always_success_dep.clone(),
+        ]
+    }
+}
+
+pub struct ProxyLockTestContext {
+    pub context: Context,
+    pub pausable_udt_out_point: OutPoint,
+    pub always_success_dep: CellDep,
+    pub pausable_udt_dep: CellDep,
+    pub proxy_lock_dep: CellDep,
+    // Stands in for the multisig holding the proxy cell
+    pub multisig_lock_script: Script,
+    // Stands in for the Type ID of the proxy cell
+    pub proxy_type_script: Script,
+    pub proxy_lock_script: Script,
+    pub pausable_udt_type_script: Script,
+    pub normal_user_lock_script: Script,
+    pub paused_user_lock_script: Script,
+}
+
+impl ProxyLockTestContext {
+    pub fn create_proxy_cell(&mut self) -> OutPoint {
+        self.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(self.multisig_lock_script.clone())
+                .type_(Some(self.proxy_type_script.clone()).pack())
+                .build(),
+            Bytes::default(),
+        )
+    }
+
+    pub fn proxy_cell_output(&self) -> CellOutput {
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(self.multisig_lock_script.clone())
+            .type_(Some(self.proxy_type_script.clone()).pack())
+            .build()
+    }
+
+    pub fn build_registry_type_script(&mut self, type_id: [u8; 32]) -> Script {
+        let mut args = self
+            .proxy_lock_script
+            .calc_script_hash()
+            .as_bytes()
+            .to_vec();
+        args.extend_from_slice(&type_id);
+        self.context
+            .build_script(&self.pausable_udt_out_point, Bytes::from(args))
+            .expect("script")
+    }
+
+    pub fn pause_list_cell_output(&self, registry_type_script: &Script) -> CellOutput {
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(self.proxy_lock_script.clone())
+            .type_(Some(registry_type_script.clone()).pack())
+            .build()
+    }
+
+    pub fn create_pause_list_cell(&mut self, pause_list: Vec<[u8; 32]>) -> (OutPoint, Script) {
+        let registry_type_script = self.build_registry_type_script([7u8; 32]);
+        let data = PausableRegistryData {
+            pause_list,
+            ..Default::default()
+        };
+        let out_point = self.context.create_cell(
+            self.pause_list_cell_output(&registry_type_script),
+            Bytes::from(serde_molecule::to_vec(&data, false).expect("serialize")),
+        );
+        (out_point, registry_type_script)
+    }
+
+    pub fn cell_deps(&self) -> Vec<CellDep> {
+        vec![
+            self.pausable_udt_dep.clone(),
+            self.proxy_lock_dep.clone(),
+            self.always_success_dep.clone(),
+        ]
+    }
+}
+
+pub fn build_proxy_lock_test_context() -> ProxyLockTestContext {
+    let mut context = Context::default();
+    let loader = Loader::default();
+    let pausable_udt_out_point = context.deploy_cell(loader.load_binary("pausable-udt"));
+    let pausable_udt_dep = CellDep::new_builder()
+        .out_point(pausable_udt_out_point.clone())
+        .build();
+    let proxy_lock_out_point = context.deploy_cell(loader.load_binary("proxy-lock"));
+    let proxy_lock_dep = CellDep::new_builder()
+        .out_point(proxy_lock_out_point.clone())
+        .build();
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let always_success_dep = CellDep::new_builder()
+        .out_point(always_success_out_point.clone())
+        .build();
+
+    let multisig_lock_script = context
+        .build_script(&always_success_out_point, Bytes::from("multisig"))
+        .expect("script");
+    let proxy_type_script = context
+        .build_script(&always_success_out_point, Bytes::from("proxy"))
+        .expect("script");
+    let proxy_lock_script = context
+        .build_script(
+            &proxy_lock_out_point,
+            proxy_type_script.calc_script_hash().as_bytes(),
+        )
+        .expect("script");
+    // The proxy lock is the owner of the token, so consuming any cell under it is owner mode
+    let pausable_udt_type_script = context
+        .build_script(
+            &pausable_udt_out_point,
+            proxy_lock_script.calc_script_hash().as_bytes(),
+        )
+        .expect("script");
+    let normal_user_lock_script = context
+        .build_script(&always_success_out_point, Bytes::from("normal user"))
+        .expect("script");
+    let paused_user_lock_script = context
+        .build_script(&always_success_out_point, Bytes::from("paused user"))
+        .expect("script");
+
+    ProxyLockTestContext {
+        context,
+        pausable_udt_out_point,
+        always_success_dep,
+        pausable_udt_dep,
+        proxy_lock_dep,
+        multisig_lock_script,
+        proxy_type_script,
+        proxy_lock_script,
+        pausable_udt_type_script,
+        normal_user_lock_script,
+        paused_user_lock_script,
+    }
+}
+
+pub fn calc_type_id(first_cell_input: &CellInput, output_index: u64) -> [u8; 32] {
+    let mut hasher = ckb_hash::new_blake2b();
+    hasher.update(first_cell_input.as_slice());
+    hasher.update(&output_index.to_le_bytes());
+    let mut ret = [0; 32];
+    hasher.finalize(&mut ret);
+    ret
+}
+
+pub fn assert_script_error(err: ckb_testtool::ckb_error::Error, err_code: i8) {
+    let error_string = err.to_string();
+    assert!(
+        error_string.contains(format!("error code {} ", err_code).as_str()),
+        "error_string: {}, expected_error_code: {}",
+        error_string,
+        err_code
+    );
+}
@@ -0,0 +1,45 @@
+#![no_std]
+#![cfg_attr(not(test), no_main)]
+
+#[cfg(test)]
+extern crate alloc;
+
+use ckb_std::ckb_constants::Source;
+use ckb_std::ckb_types::{bytes::Bytes, prelude::*};
+use ckb_std::debug;
+#[cfg(not(test))]
+use ckb_std::default_alloc;
+use ckb_std::high_level::{load_cell_type_hash, load_script, QueryIter};
+#[cfg(not(test))]
+ckb_std::entry!(program_entry);
+#[cfg(not(test))]
+default_alloc!();
+
+use error::Error;
+
+mod error;
+
+// NOTE: The args of a proxy lock are the type script hash of a proxy cell (usually a Type ID cell held by a multisig). Cells under the proxy lock unlock whenever that proxy cell is consumed in the same transaction, so whoever can spend the proxy cell controls all of them, and handing over control only takes moving the proxy cell.
+fn program_entry_wrap() -> Result<(), Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let proxy_type_hash: [u8; 32] = args
+        .as_ref()
+        .try_into()
+        .map_err(|_| Error::InvalidProxyLockArgs)?;
+    debug!("proxy_type_hash: {:?}", proxy_type_hash);
+
+    if !QueryIter::new(load_cell_type_hash, Source::Input)
+        .any(|type_hash| type_hash == Some(proxy_type_hash))
+    {
+        return Err(Error::ProxyCellNotFound);
+    }
+    Ok(())
+}
+
+pub fn program_entry() -> i8 {
+    match program_entry_wrap() {
+        Ok(_) => 0,
+        Err(err) => err as i8,
+    }
+}